@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS subject_room_types (
+    subject_id INTEGER NOT NULL,
+    room_type TEXT NOT NULL,
+    PRIMARY KEY (subject_id, room_type),
+    FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS teacher_unavailability (
+    id INTEGER PRIMARY KEY,
+    teacher_id INTEGER NOT NULL,
+    day TEXT NOT NULL,
+    module_index INTEGER NOT NULL,
+    reason TEXT,
+    FOREIGN KEY (teacher_id) REFERENCES teachers(id) ON DELETE CASCADE,
+    UNIQUE (teacher_id, day, module_index)
+);
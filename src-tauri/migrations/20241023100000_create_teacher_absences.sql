@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS teacher_absences (
+    id INTEGER PRIMARY KEY,
+    teacher_id INTEGER NOT NULL,
+    start_date TEXT NOT NULL,
+    end_date TEXT NOT NULL,
+    reason TEXT,
+    FOREIGN KEY (teacher_id) REFERENCES teachers(id) ON DELETE CASCADE
+);
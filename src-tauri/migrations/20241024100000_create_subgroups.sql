@@ -0,0 +1,38 @@
+CREATE TABLE IF NOT EXISTS subgroups (
+    id INTEGER PRIMARY KEY,
+    group_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    students INTEGER,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    UNIQUE (group_id, name)
+);
+
+-- SQLite no permite quitar un UNIQUE, se vuelve a crear la tabla de asignaciones.
+-- Un modulo del grupo puede tener una asignacion para todo el grupo (subgroup_id nulo)
+-- o varias asignaciones en paralelo, una por subgrupo.
+CREATE TABLE assignments_new (
+    id INTEGER PRIMARY KEY,
+    group_id INTEGER NOT NULL,
+    day TEXT NOT NULL,
+    module_index INTEGER NOT NULL,
+    subject_id INTEGER NOT NULL,
+    teacher_id INTEGER NOT NULL,
+    classroom_id INTEGER,
+    subgroup_id INTEGER,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE,
+    FOREIGN KEY (teacher_id) REFERENCES teachers(id) ON DELETE CASCADE,
+    FOREIGN KEY (classroom_id) REFERENCES classroom(id) ON DELETE CASCADE,
+    FOREIGN KEY (subgroup_id) REFERENCES subgroups(id) ON DELETE CASCADE
+);
+
+INSERT INTO assignments_new (id, group_id, day, module_index, subject_id, teacher_id, classroom_id)
+SELECT id, group_id, day, module_index, subject_id, teacher_id, classroom_id FROM assignments;
+
+DROP TABLE assignments;
+ALTER TABLE assignments_new RENAME TO assignments;
+
+CREATE UNIQUE INDEX assignments_group_slot
+    ON assignments (group_id, day, module_index) WHERE subgroup_id IS NULL;
+CREATE UNIQUE INDEX assignments_subgroup_slot
+    ON assignments (group_id, day, module_index, subgroup_id) WHERE subgroup_id IS NOT NULL;
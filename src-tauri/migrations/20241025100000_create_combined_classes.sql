@@ -0,0 +1,8 @@
+-- Clase compartida por varios grupos (ej: asamblea, optativa, educacion fisica).
+-- Cada grupo tiene su propia fila en assignments ligada por combined_id.
+CREATE TABLE IF NOT EXISTS combined_classes (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+ALTER TABLE assignments ADD COLUMN combined_id INTEGER REFERENCES combined_classes(id) ON DELETE CASCADE;
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS students (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    father_lastname TEXT NOT NULL,
+    mother_lastname TEXT,
+    enrollment TEXT,
+    email TEXT,
+    group_id INTEGER,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS student_electives (
+    student_id INTEGER NOT NULL,
+    subject_id INTEGER NOT NULL,
+    PRIMARY KEY (student_id, subject_id),
+    FOREIGN KEY (student_id) REFERENCES students(id) ON DELETE CASCADE,
+    FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE
+);
+
+-- Las materias optativas del grupo solo las toman los alumnos que las eligen
+ALTER TABLE groups_subjects ADD COLUMN elective INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,20 @@
+-- Plan de estudios por carrera y grado, la carrera vacia aplica a grupos sin carrera
+CREATE TABLE IF NOT EXISTS curriculum_templates (
+    id INTEGER PRIMARY KEY,
+    career TEXT NOT NULL DEFAULT '',
+    grade INTEGER NOT NULL,
+    UNIQUE (career, grade)
+);
+
+CREATE TABLE IF NOT EXISTS curriculum_subjects (
+    template_id INTEGER NOT NULL,
+    subject_id INTEGER NOT NULL,
+    hours INTEGER,
+    elective INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (template_id, subject_id),
+    FOREIGN KEY (template_id) REFERENCES curriculum_templates(id) ON DELETE CASCADE,
+    FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE
+);
+
+-- Horas de la materia en el grupo, si es nulo se usa required_modules de la materia
+ALTER TABLE groups_subjects ADD COLUMN hours INTEGER;
@@ -0,0 +1,11 @@
+-- Plan de carga: que profesor imparte cada materia a cada grupo
+CREATE TABLE IF NOT EXISTS course_loads (
+    id INTEGER PRIMARY KEY,
+    group_id INTEGER NOT NULL,
+    subject_id INTEGER NOT NULL,
+    teacher_id INTEGER NOT NULL,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE,
+    FOREIGN KEY (teacher_id) REFERENCES teachers(id) ON DELETE CASCADE,
+    UNIQUE (group_id, subject_id)
+);
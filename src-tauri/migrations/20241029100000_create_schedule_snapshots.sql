@@ -0,0 +1,21 @@
+-- Copias con nombre del horario (ej: borrador antes de la revision del director)
+CREATE TABLE IF NOT EXISTS schedule_snapshots (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Asignaciones guardadas en cada copia, sin llaves foraneas para conservar la copia
+-- aunque despues se borren grupos, materias o profesores
+CREATE TABLE IF NOT EXISTS snapshot_assignments (
+    snapshot_id INTEGER NOT NULL,
+    group_id INTEGER NOT NULL,
+    day TEXT NOT NULL,
+    module_index INTEGER NOT NULL,
+    subject_id INTEGER NOT NULL,
+    teacher_id INTEGER NOT NULL,
+    classroom_id INTEGER,
+    subgroup_id INTEGER,
+    combined_id INTEGER,
+    FOREIGN KEY (snapshot_id) REFERENCES schedule_snapshots(id) ON DELETE CASCADE
+);
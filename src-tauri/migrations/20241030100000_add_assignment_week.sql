@@ -0,0 +1,12 @@
+-- Patron de semanas de la asignacion: todas las semanas ('all'), semana A ('a') o semana B ('b').
+-- Dos clases quincenales (una en A y otra en B) pueden compartir el mismo modulo.
+ALTER TABLE assignments ADD COLUMN week TEXT NOT NULL DEFAULT 'all' CHECK (week IN ('all', 'a', 'b'));
+ALTER TABLE snapshot_assignments ADD COLUMN week TEXT NOT NULL DEFAULT 'all';
+
+DROP INDEX IF EXISTS assignments_group_slot;
+DROP INDEX IF EXISTS assignments_subgroup_slot;
+
+CREATE UNIQUE INDEX assignments_group_slot
+    ON assignments (group_id, day, module_index, week) WHERE subgroup_id IS NULL;
+CREATE UNIQUE INDEX assignments_subgroup_slot
+    ON assignments (group_id, day, module_index, subgroup_id, week) WHERE subgroup_id IS NOT NULL;
@@ -0,0 +1,21 @@
+-- Examenes de fin de periodo, uno por materia de cada grupo
+CREATE TABLE IF NOT EXISTS exams (
+    id INTEGER PRIMARY KEY,
+    group_id INTEGER NOT NULL,
+    subject_id INTEGER NOT NULL,
+    date TEXT NOT NULL,
+    module_index INTEGER NOT NULL,
+    classroom_id INTEGER,
+    invigilator_id INTEGER,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (subject_id) REFERENCES subjects(id) ON DELETE CASCADE,
+    FOREIGN KEY (classroom_id) REFERENCES classroom(id) ON DELETE SET NULL,
+    FOREIGN KEY (invigilator_id) REFERENCES teachers(id) ON DELETE SET NULL,
+    -- Un examen por grupo por dia
+    UNIQUE (group_id, date)
+);
+
+CREATE UNIQUE INDEX exams_classroom_slot
+    ON exams (classroom_id, date, module_index) WHERE classroom_id IS NOT NULL;
+CREATE UNIQUE INDEX exams_invigilator_slot
+    ON exams (invigilator_id, date, module_index) WHERE invigilator_id IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Definicion del calendario escolar (una sola fila)
+-- week_a_start es una fecha dentro de una semana A, si es nulo la primera semana del periodo es A
+CREATE TABLE IF NOT EXISTS school_calendar (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    term_start TEXT NOT NULL,
+    term_end TEXT NOT NULL,
+    week_a_start TEXT
+);
+
+-- Horario de cada modulo del dia con formato HH:MM
+CREATE TABLE IF NOT EXISTS module_times (
+    module_index INTEGER PRIMARY KEY,
+    start_time TEXT NOT NULL,
+    end_time TEXT NOT NULL
+);
@@ -0,0 +1,103 @@
+-- Indice de busqueda de texto completo sobre profesores, materias, grupos y salones.
+-- remove_diacritics ignora los acentos tanto en los datos como en la busqueda.
+-- El rowid es id * 4 + tipo (0 profesor, 1 materia, 2 grupo, 3 salon) para poder
+-- actualizar cada fila sin recorrer el indice. Los triggers lo mantienen al dia.
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    kind UNINDEXED,
+    entity_id UNINDEXED,
+    title,
+    content,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+-- Profesores: nombre completo y correo
+INSERT INTO search_index (rowid, kind, entity_id, title, content)
+SELECT id * 4, 'teacher', id,
+       name || ' ' || father_lastname || COALESCE(' ' || mother_lastname, ''),
+       COALESCE(email, '')
+FROM teachers;
+
+CREATE TRIGGER IF NOT EXISTS search_teachers_insert AFTER INSERT ON teachers BEGIN
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4, 'teacher', new.id,
+            new.name || ' ' || new.father_lastname || COALESCE(' ' || new.mother_lastname, ''),
+            COALESCE(new.email, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_teachers_update AFTER UPDATE ON teachers BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4;
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4, 'teacher', new.id,
+            new.name || ' ' || new.father_lastname || COALESCE(' ' || new.mother_lastname, ''),
+            COALESCE(new.email, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_teachers_delete AFTER DELETE ON teachers BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4;
+END;
+
+-- Materias: nombre, abreviatura y especialidad
+INSERT INTO search_index (rowid, kind, entity_id, title, content)
+SELECT id * 4 + 1, 'subject', id, name, shorten || ' ' || spec
+FROM subjects;
+
+CREATE TRIGGER IF NOT EXISTS search_subjects_insert AFTER INSERT ON subjects BEGIN
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4 + 1, 'subject', new.id, new.name, new.shorten || ' ' || new.spec);
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_subjects_update AFTER UPDATE ON subjects BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4 + 1;
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4 + 1, 'subject', new.id, new.name, new.shorten || ' ' || new.spec);
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_subjects_delete AFTER DELETE ON subjects BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4 + 1;
+END;
+
+-- Grupos: grado y grupo juntos (1A) y separados (1 A), y carrera
+INSERT INTO search_index (rowid, kind, entity_id, title, content)
+SELECT id * 4 + 2, 'group', id, grade || "group",
+       grade || ' ' || "group" || COALESCE(' ' || career, '')
+FROM groups;
+
+CREATE TRIGGER IF NOT EXISTS search_groups_insert AFTER INSERT ON groups BEGIN
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4 + 2, 'group', new.id, new.grade || new."group",
+            new.grade || ' ' || new."group" || COALESCE(' ' || new.career, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_groups_update AFTER UPDATE ON groups BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4 + 2;
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4 + 2, 'group', new.id, new.grade || new."group",
+            new.grade || ' ' || new."group" || COALESCE(' ' || new.career, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_groups_delete AFTER DELETE ON groups BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4 + 2;
+END;
+
+-- Salones: edificio y numero juntos (A101) y separados (A 101), y tipo
+INSERT INTO search_index (rowid, kind, entity_id, title, content)
+SELECT id * 4 + 3, 'classroom', id, building_id || building_number,
+       building_id || ' ' || building_number || COALESCE(' ' || building_type, '')
+FROM classroom;
+
+CREATE TRIGGER IF NOT EXISTS search_classroom_insert AFTER INSERT ON classroom BEGIN
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4 + 3, 'classroom', new.id, new.building_id || new.building_number,
+            new.building_id || ' ' || new.building_number || COALESCE(' ' || new.building_type, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_classroom_update AFTER UPDATE ON classroom BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4 + 3;
+    INSERT INTO search_index (rowid, kind, entity_id, title, content)
+    VALUES (new.id * 4 + 3, 'classroom', new.id, new.building_id || new.building_number,
+            new.building_id || ' ' || new.building_number || COALESCE(' ' || new.building_type, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_classroom_delete AFTER DELETE ON classroom BEGIN
+    DELETE FROM search_index WHERE rowid = old.id * 4 + 3;
+END;
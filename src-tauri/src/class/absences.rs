@@ -0,0 +1,272 @@
+use crate::class::school_calendar::fetch_school_calendar;
+use crate::class::teachers::{SimpleTeacher, TEACHER_LOAD};
+use crate::db::AppState;
+use crate::util::calendar::{dates_between, day_name, parse_date, week_of, weeks_overlap};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::Row;
+
+/// Estructura de una falta de un profesor
+/// Las fechas se guardan con formato `YYYY-MM-DD` y el rango incluye ambas fechas
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TeacherAbsence {
+    pub id: Option<i16>,
+    pub teacher_id: i16,
+    pub start_date: String,
+    pub end_date: String,
+    pub reason: Option<String>,
+}
+
+/// Profesor que puede cubrir una clase
+/// `load` es la cantidad de modulos que ya tiene asignados en el horario
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubstituteCandidate {
+    pub teacher: SimpleTeacher,
+    pub load: i16,
+}
+
+/// Clase afectada por una falta junto con los profesores que la pueden cubrir
+/// Una clase compartida aparece una sola vez con todos sus grupos
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AffectedAssignment {
+    pub date: String,
+    pub assignment_id: i16,
+    pub group_ids: Vec<i16>,
+    pub combined_id: Option<i16>,
+    pub day: String,
+    pub module_index: i16,
+    pub subject_id: i16,
+    pub week: String,
+    pub substitutes: Vec<SubstituteCandidate>,
+}
+
+/// Funcion para registrar una falta de un profesor
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `absence` - Falta (profesor, fecha de inicio y fin, motivo)
+/// Retorna el ID de la falta si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn add_teacher_absence(
+    pool: tauri::State<'_, AppState>,
+    absence: TeacherAbsence,
+) -> Result<i16, String> {
+    let start = parse_date(&absence.start_date)?;
+    let end = parse_date(&absence.end_date)?;
+    if end < start {
+        return Err("The absence end date is before its start date".to_string());
+    }
+
+    let id: i16 = sqlx::query_scalar(
+        "
+        INSERT INTO teacher_absences (teacher_id, start_date, end_date, reason)
+        VALUES (?1, ?2, ?3, ?4)
+        RETURNING id
+        ",
+    )
+    .bind(absence.teacher_id)
+    .bind(start.to_string())
+    .bind(end.to_string())
+    .bind(absence.reason)
+    .fetch_one(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to create teacher absence: {}", e))?;
+
+    Ok(id)
+}
+
+/// Funcion para obtener las faltas registradas
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `teacher_id` - ID del profesor (opcional, si es nulo regresa las de todos)
+/// Retorna un vector con las faltas ordenadas por fecha
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_teacher_absences(
+    pool: tauri::State<'_, AppState>,
+    teacher_id: Option<i16>,
+) -> Result<Vec<TeacherAbsence>, String> {
+    let absences: Vec<TeacherAbsence> = sqlx::query_as::<_, TeacherAbsence>(
+        "
+        SELECT * FROM teacher_absences
+        WHERE ?1 IS NULL OR teacher_id = ?1
+        ORDER BY start_date
+        ",
+    )
+    .bind(teacher_id)
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get teacher absences: {}", e))?;
+
+    Ok(absences)
+}
+
+/// Funcion para eliminar una falta
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID de la falta
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_teacher_absence(
+    pool: tauri::State<'_, AppState>,
+    id: i16,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM teacher_absences WHERE id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete teacher absence: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para obtener las clases afectadas por una falta y sus posibles suplentes
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `absence_id` - ID de la falta
+/// Retorna cada clase del profesor en las fechas de la falta con los profesores que
+/// pueden dar la misma materia, estan libres en ese modulo y no faltan ese dia.
+/// Si el calendario escolar esta definido solo cuentan las clases de la semana (A o B)
+/// de cada fecha. Los suplentes se ordenan de menor a mayor carga
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_absence_substitutes(
+    pool: tauri::State<'_, AppState>,
+    absence_id: i16,
+) -> Result<Vec<AffectedAssignment>, String> {
+    let absence: TeacherAbsence =
+        sqlx::query_as::<_, TeacherAbsence>("SELECT * FROM teacher_absences WHERE id = ?1")
+            .bind(absence_id)
+            .fetch_optional(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get teacher absence: {}", e))?
+            .ok_or_else(|| format!("Absence {} not found", absence_id))?;
+
+    let start = parse_date(&absence.start_date)?;
+    let end = parse_date(&absence.end_date)?;
+
+    // Sin calendario no se sabe si la fecha es semana A o B y cuentan las dos
+    let week_a = match fetch_school_calendar(&pool.db).await? {
+        Some(calendar) => Some(parse_date(
+            calendar
+                .week_a_start
+                .as_deref()
+                .unwrap_or(&calendar.term_start),
+        )?),
+        None => None,
+    };
+
+    let mut affected: Vec<AffectedAssignment> = Vec::new();
+    for date in dates_between(start, end) {
+        let day = day_name(date);
+        let date_week = week_a.map(|week_a| week_of(date, week_a)).unwrap_or("all");
+        let date = date.to_string();
+
+        // Clases del profesor ese dia de la semana
+        let rows = sqlx::query(
+            "
+            SELECT id, group_id, module_index, subject_id, week, combined_id
+            FROM assignments
+            WHERE teacher_id = ?1 AND day = ?2
+            ORDER BY module_index, id
+            ",
+        )
+        .bind(absence.teacher_id)
+        .bind(day)
+        .fetch_all(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to get affected assignments: {}", e))?;
+
+        // Las filas de una clase compartida se juntan en una sola clase
+        let mut classes: Vec<AffectedAssignment> = Vec::new();
+        for row in rows {
+            let week: String = row.get("week");
+            if !weeks_overlap(&week, date_week) {
+                continue;
+            }
+            let combined_id: Option<i16> = row.get("combined_id");
+            let group_id: i16 = row.get("group_id");
+            let module_index: i16 = row.get("module_index");
+            if let Some(class) = classes.iter_mut().find(|c| {
+                combined_id.is_some()
+                    && c.combined_id == combined_id
+                    && c.module_index == module_index
+            }) {
+                class.group_ids.push(group_id);
+                continue;
+            }
+            classes.push(AffectedAssignment {
+                date: date.clone(),
+                assignment_id: row.get("id"),
+                group_ids: vec![group_id],
+                combined_id,
+                day: day.to_string(),
+                module_index,
+                subject_id: row.get("subject_id"),
+                week,
+                substitutes: Vec::new(),
+            });
+        }
+
+        for mut class in classes {
+            // Si no se sabe la semana de la fecha se usa la de la clase
+            let week = if date_week == "all" {
+                class.week.clone()
+            } else {
+                date_week.to_string()
+            };
+
+            // Profesores de la misma materia libres en ese modulo, ordenados por carga
+            let substitutes = sqlx::query(&format!(
+                "
+                SELECT t.id, t.name, t.father_lastname,
+                       (SELECT {} FROM assignments l WHERE l.teacher_id = t.id) AS load
+                FROM teachers t
+                JOIN teacher_subjects ts ON ts.teacher_id = t.id
+                WHERE ts.subject_id = ?1
+                AND t.id != ?2
+                AND NOT EXISTS (
+                    SELECT 1 FROM assignments a
+                    WHERE a.teacher_id = t.id AND a.day = ?3 AND a.module_index = ?4
+                    AND (a.week = 'all' OR ?6 = 'all' OR a.week = ?6)
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM teacher_unavailability u
+                    WHERE u.teacher_id = t.id AND u.day = ?3 AND u.module_index = ?4
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM teacher_absences ab
+                    WHERE ab.teacher_id = t.id AND ?5 BETWEEN ab.start_date AND ab.end_date
+                )
+                ORDER BY load, t.father_lastname, t.name
+                ",
+                TEACHER_LOAD
+            ))
+            .bind(class.subject_id)
+            .bind(absence.teacher_id)
+            .bind(day)
+            .bind(class.module_index)
+            .bind(&date)
+            .bind(&week)
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get substitute teachers: {}", e))?
+            .into_iter()
+            .map(|s| SubstituteCandidate {
+                teacher: SimpleTeacher {
+                    id: s.get("id"),
+                    name: s.get("name"),
+                    father_lastname: s.get("father_lastname"),
+                },
+                load: s.get("load"),
+            })
+            .collect();
+
+            class.substitutes = substitutes;
+            affected.push(class);
+        }
+    }
+
+    Ok(affected)
+}
@@ -1,17 +1,47 @@
 use crate::db::AppState;
+use crate::util::calendar::is_school_slot;
 use futures::TryStreamExt; // Para poder usar try_next() en los streams
 use serde::{Deserialize, Serialize};
-use sqlx::prelude::FromRow;
+use sqlx::error::Error as SqlxError;
+use sqlx::{sqlite::SqliteRow, Executor, FromRow, Row, Sqlite};
+
+/// Bloque (dia y modulo) en el que el salon no se puede utilizar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AvailabilityBlock {
+    pub day: String,
+    pub module_index: i16,
+}
 
 /// Estructural salon
 /// Se utiliza para mapear los datos de la base de datos a un objeto en Rust
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Classroom {
     pub id: Option<i16>,
     pub building_id: Option<String>, // Puede ser una letra o numero entonces lo dejaremos como String
     pub building_number: i16, // Numero de aula, lo que sigue despues del building_id (ejemplo: 303)
     pub building_type: Option<String>,
     pub capacity: Option<i16>,
+    #[serde(default)]
+    pub availability: Vec<AvailabilityBlock>, // Bloques en los que el salon no esta disponible
+}
+
+// Implement FromRow for Classroom
+impl<'r> FromRow<'r, SqliteRow> for Classroom {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, SqlxError> {
+        // La disponibilidad se guarda como JSON en la columna `availability`
+        let id: Option<i16> = row.try_get("id")?;
+        let availability = parse_availability(id.unwrap_or_default(), row.try_get("availability")?)
+            .map_err(|e| SqlxError::Decode(e.into()))?;
+
+        Ok(Classroom {
+            id,
+            building_id: row.try_get("building_id")?,
+            building_number: row.try_get("building_number")?,
+            building_type: row.try_get("building_type")?,
+            capacity: row.try_get("capacity")?,
+            availability,
+        })
+    }
 }
 
 /// Funcion para crear un nuevo elemento
@@ -62,13 +92,17 @@ pub async fn create_classrooms(
 
     for c in classroom {
         println!("Aula: {:?}", c);
+        let availability = serde_json::to_string(&c.availability)
+            .map_err(|e| format!("Failed to serialize classroom availability: {}", e))?;
+
         sqlx::query(
-            r#"INSERT INTO classroom (building_id, building_number, building_type, capacity) VALUES (?1, ?2, ?3, ?4)"#,
+            r#"INSERT INTO classroom (building_id, building_number, building_type, capacity, availability) VALUES (?1, ?2, ?3, ?4, ?5)"#,
         )
         .bind(c.building_id)
         .bind(c.building_number)
         .bind(c.building_type)
         .bind(c.capacity)
+        .bind(availability)
         .execute(&mut tx)
         .await
         .map_err(|e| format!("Error creating the classroom, error: {}", e))?;
@@ -166,3 +200,317 @@ pub async fn update_classroom(
 
     Ok(())
 }
+
+/// Funcion para obtener los bloques en los que un salon no esta disponible
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del salon
+/// Retorna un vector con los bloques no disponibles
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_classroom_availability(
+    pool: tauri::State<'_, AppState>,
+    id: i16,
+) -> Result<Vec<AvailabilityBlock>, String> {
+    fetch_availability(&pool.db, id).await
+}
+
+/// Funcion para reemplazar todos los bloques no disponibles de un salon
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del salon
+/// * `availability` - Bloques (dia y modulo) en los que no se puede usar el salon
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn set_classroom_availability(
+    pool: tauri::State<'_, AppState>,
+    id: i16,
+    availability: Vec<AvailabilityBlock>,
+) -> Result<(), String> {
+    for block in &availability {
+        check_block(&block.day, block.module_index)?;
+    }
+    store_availability(&pool.db, id, &availability).await
+}
+
+/// Funcion para marcar un bloque como no disponible en un salon
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del salon
+/// * `day` - Dia del bloque
+/// * `module_index` - Modulo del bloque
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn add_classroom_unavailable_block(
+    pool: tauri::State<'_, AppState>,
+    id: i16,
+    day: String,
+    module_index: i16,
+) -> Result<(), String> {
+    check_block(&day, module_index)?;
+
+    // Se lee y se guarda en la misma transaccion para no perder cambios de otra edicion
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let mut availability = fetch_availability(&mut tx, id).await?;
+    let block = AvailabilityBlock { day, module_index };
+
+    // Solo se agrega si no existia antes
+    if !availability.contains(&block) {
+        availability.push(block);
+        store_availability(&mut tx, id, &availability).await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para volver a marcar un bloque como disponible en un salon
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del salon
+/// * `day` - Dia del bloque
+/// * `module_index` - Modulo del bloque
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn remove_classroom_unavailable_block(
+    pool: tauri::State<'_, AppState>,
+    id: i16,
+    day: String,
+    module_index: i16,
+) -> Result<(), String> {
+    check_block(&day, module_index)?;
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let mut availability = fetch_availability(&mut tx, id).await?;
+    availability.retain(|b| !(b.day == day && b.module_index == module_index));
+    store_availability(&mut tx, id, &availability).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Checa si el salon se puede usar en el dia y modulo indicados
+/// Se utiliza al asignar un salon a un modulo del horario
+pub async fn is_classroom_available<'e, E>(
+    executor: E,
+    id: i16,
+    day: &str,
+    module_index: i16,
+) -> Result<bool, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let availability = fetch_availability(executor, id).await?;
+
+    Ok(!availability
+        .iter()
+        .any(|b| b.day == day && b.module_index == module_index))
+}
+
+/// Checa si el tipo del salon cumple con los tipos que requiere la materia
+/// Si la materia no tiene requisitos cualquier salon es compatible
+pub async fn is_classroom_compatible<'e, E>(
+    executor: E,
+    id: i16,
+    subject_id: i16,
+) -> Result<bool, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let compatible: bool = sqlx::query_scalar(
+        "
+        SELECT NOT EXISTS (SELECT 1 FROM subject_room_types WHERE subject_id = ?2)
+            OR EXISTS (
+                SELECT 1 FROM subject_room_types r
+                JOIN classroom c ON LOWER(TRIM(c.building_type)) = LOWER(TRIM(r.room_type))
+                WHERE r.subject_id = ?2 AND c.id = ?1
+            )
+        ",
+    )
+    .bind(id)
+    .bind(subject_id)
+    .fetch_one(executor)
+    .await
+    .map_err(|e| format!("Failed to check classroom type: {}", e))?;
+
+    Ok(compatible)
+}
+
+/// Funcion para obtener los salones donde se puede dar una materia en un modulo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `subject_id` - ID de la materia
+/// * `day` - Dia del modulo
+/// * `module_index` - Modulo
+/// Retorna los salones de un tipo compatible con la materia, disponibles y sin ocupar en ese modulo
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_compatible_classrooms(
+    pool: tauri::State<'_, AppState>,
+    subject_id: i16,
+    day: String,
+    module_index: i16,
+) -> Result<Vec<Classroom>, String> {
+    let classrooms: Vec<Classroom> = sqlx::query_as::<_, Classroom>(
+        "
+        SELECT c.* FROM classroom c
+        WHERE NOT EXISTS (
+            SELECT 1 FROM assignments a
+            WHERE a.classroom_id = c.id AND a.day = ?2 AND a.module_index = ?3
+        )
+        AND (
+            NOT EXISTS (SELECT 1 FROM subject_room_types WHERE subject_id = ?1)
+            OR EXISTS (
+                SELECT 1 FROM subject_room_types r
+                WHERE r.subject_id = ?1
+                AND LOWER(TRIM(r.room_type)) = LOWER(TRIM(c.building_type))
+            )
+        )
+        ",
+    )
+    .bind(subject_id)
+    .bind(&day)
+    .bind(module_index)
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get compatible classrooms: {}", e))?;
+
+    // Quitar los salones que tienen el bloque marcado como no disponible
+    Ok(classrooms
+        .into_iter()
+        .filter(|c| {
+            !c.availability
+                .iter()
+                .any(|b| b.day == day && b.module_index == module_index)
+        })
+        .collect())
+}
+
+/// Obtiene la disponibilidad guardada como JSON en la tabla
+async fn fetch_availability<'e, E>(executor: E, id: i16) -> Result<Vec<AvailabilityBlock>, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let availability: Option<Option<String>> =
+        sqlx::query_scalar("SELECT availability FROM classroom WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(executor)
+            .await
+            .map_err(|e| format!("Failed to get classroom availability: {}", e))?;
+
+    match availability {
+        Some(availability) => parse_availability(id, availability),
+        None => Err(format!("Classroom {} not found", id)),
+    }
+}
+
+/// Convierte el JSON de la disponibilidad de un salon, vacio si no tiene
+/// Un JSON invalido es un error para no tratar el salon como siempre disponible
+pub fn parse_availability(
+    id: i16,
+    availability: Option<String>,
+) -> Result<Vec<AvailabilityBlock>, String> {
+    match availability {
+        Some(json) if !json.trim().is_empty() => serde_json::from_str(&json)
+            .map_err(|_| format!("Invalid availability for classroom {}", id)),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Valida que el bloque este dentro de los dias y modulos de la escuela
+fn check_block(day: &str, module_index: i16) -> Result<(), String> {
+    if is_school_slot(day, module_index) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Module {} on {} is outside the school calendar",
+            module_index, day
+        ))
+    }
+}
+
+/// Guarda la disponibilidad como JSON en la tabla
+async fn store_availability<'e, E>(
+    executor: E,
+    id: i16,
+    availability: &[AvailabilityBlock],
+) -> Result<(), String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let availability = serde_json::to_string(availability)
+        .map_err(|e| format!("Failed to serialize classroom availability: {}", e))?;
+
+    sqlx::query("UPDATE classroom SET availability = ?1 WHERE id = ?2")
+        .bind(availability)
+        .bind(id)
+        .execute(executor)
+        .await
+        .map_err(|e| format!("Failed to update classroom availability: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_availability_reads_blocks() {
+        let blocks = parse_availability(
+            1,
+            Some("[{\"day\":\"Lunes\",\"module_index\":2}]".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            blocks,
+            vec![AvailabilityBlock {
+                day: "Lunes".to_string(),
+                module_index: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_availability_treats_missing_json_as_always_available() {
+        assert!(parse_availability(1, None).unwrap().is_empty());
+        assert!(parse_availability(1, Some(" ".to_string()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn parse_availability_rejects_invalid_json() {
+        assert_eq!(
+            parse_availability(7, Some("[{\"day\":\"Lunes\"".to_string())),
+            Err("Invalid availability for classroom 7".to_string())
+        );
+    }
+
+    #[test]
+    fn check_block_requires_a_school_slot() {
+        assert!(check_block("Lunes", 0).is_ok());
+        assert!(check_block("lunes", 0).is_err());
+        assert!(check_block("Sabado", 0).is_err());
+        assert!(check_block("Viernes", 99).is_err());
+    }
+}
@@ -0,0 +1,385 @@
+use crate::db::AppState;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::collections::HashMap;
+
+/// Estructura de una carga de curso
+/// Indica que profesor imparte una materia a un grupo antes de armar el horario
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CourseLoad {
+    pub id: Option<i16>,
+    pub group_id: i16,
+    pub subject_id: i16,
+    pub teacher_id: i16,
+}
+
+/// Asignacion del horario cuyo profesor no es el del plan de carga
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CourseLoadDeviation {
+    pub assignment_id: i16,
+    pub group_id: i16,
+    pub subject_id: i16,
+    pub day: String,
+    pub module_index: i16,
+    pub teacher_id: i16,
+    pub planned_teacher_id: i16,
+}
+
+/// Seccion (materia de un grupo) dentro de una propuesta de reparto de carga
+/// `teacher_id` es nulo cuando ningun profesor puede impartir la materia
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlannedLoad {
+    pub group_id: i16,
+    pub subject_id: i16,
+    pub hours: i16,
+    pub teacher_id: Option<i16>,
+    /// La seccion ya tenia profesor en el plan de carga y se conserva
+    pub existing: bool,
+}
+
+/// Horas que tendria cada profesor con la propuesta contra sus horas comisionadas
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeacherLoadSummary {
+    pub teacher_id: i16,
+    pub commisioned_hours: i16,
+    pub planned_hours: i16,
+}
+
+/// Propuesta de reparto de carga, no se guarda hasta llamar `apply_course_load_plan`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoadPlan {
+    pub loads: Vec<PlannedLoad>,
+    pub teachers: Vec<TeacherLoadSummary>,
+}
+
+/// Funcion para obtener el plan de carga
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo (opcional, si es nulo regresa el de todos los grupos)
+/// Retorna un vector con las cargas de curso
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_course_loads(
+    pool: tauri::State<'_, AppState>,
+    group_id: Option<i16>,
+) -> Result<Vec<CourseLoad>, String> {
+    let loads: Vec<CourseLoad> = sqlx::query_as::<_, CourseLoad>(
+        "
+        SELECT * FROM course_loads
+        WHERE ?1 IS NULL OR group_id = ?1
+        ORDER BY group_id, subject_id
+        ",
+    )
+    .bind(group_id)
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get course loads: {}", e))?;
+
+    Ok(loads)
+}
+
+/// Funcion para asignar el profesor que imparte una materia a un grupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `load` - Carga de curso (grupo, materia y profesor)
+/// Si ya existia una carga para el grupo y la materia se cambia el profesor
+/// Retorna el ID de la carga si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn save_course_load(
+    pool: tauri::State<'_, AppState>,
+    load: CourseLoad,
+) -> Result<i16, String> {
+    let id: i16 = sqlx::query_scalar(
+        "
+        INSERT INTO course_loads (group_id, subject_id, teacher_id)
+        VALUES (?1, ?2, ?3)
+        ON CONFLICT (group_id, subject_id) DO UPDATE
+        SET teacher_id = excluded.teacher_id
+        RETURNING id
+        ",
+    )
+    .bind(load.group_id)
+    .bind(load.subject_id)
+    .bind(load.teacher_id)
+    .fetch_one(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to save course load: {}", e))?;
+
+    Ok(id)
+}
+
+/// Funcion para eliminar una carga de curso
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID de la carga
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_course_load(pool: tauri::State<'_, AppState>, id: i16) -> Result<(), String> {
+    sqlx::query("DELETE FROM course_loads WHERE id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete course load: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para obtener las asignaciones que no respetan el plan de carga
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Retorna un vector con las asignaciones cuyo profesor es distinto al planeado
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_course_load_deviations(
+    pool: tauri::State<'_, AppState>,
+) -> Result<Vec<CourseLoadDeviation>, String> {
+    let deviations: Vec<CourseLoadDeviation> = sqlx::query_as::<_, CourseLoadDeviation>(
+        "
+        SELECT a.id AS assignment_id, a.group_id, a.subject_id, a.day, a.module_index,
+               a.teacher_id, c.teacher_id AS planned_teacher_id
+        FROM assignments a
+        JOIN course_loads c ON c.group_id = a.group_id AND c.subject_id = a.subject_id
+        WHERE a.teacher_id != c.teacher_id
+        ORDER BY a.group_id, a.day, a.module_index
+        ",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get course load deviations: {}", e))?;
+
+    Ok(deviations)
+}
+
+/// Funcion para proponer un reparto de las materias de los grupos entre los profesores
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `keep_existing` - Si es verdadero se conservan las cargas ya guardadas
+/// Las secciones se reparten de mayor a menor prioridad de la materia. Cada seccion se
+/// da al profesor que la puede impartir con mas horas comisionadas libres, y si hay
+/// empate al de mejor desempeño y luego al de menor ID. Si ninguno tiene horas libres se
+/// da al que menos se pase
+/// No guarda nada, retorna la propuesta para revisarla
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn plan_course_loads(
+    pool: tauri::State<'_, AppState>,
+    keep_existing: bool,
+) -> Result<LoadPlan, String> {
+    // (id, horas comisionadas, desempeño)
+    let teachers: Vec<(i16, i16, i16)> = sqlx::query_as(
+        "
+        SELECT id, COALESCE(commisioned_hours, 0), COALESCE(performance, 0)
+        FROM teachers ORDER BY id
+        ",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get teachers: {}", e))?;
+
+    let qualified: Vec<(i16, i16)> =
+        sqlx::query_as("SELECT subject_id, teacher_id FROM teacher_subjects")
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get teacher subjects: {}", e))?;
+
+    let sections: Vec<Section> = sqlx::query_as(
+        "
+        SELECT gs.group_id, gs.subject_id, COALESCE(gs.hours, s.required_modules),
+               COALESCE(s.priority, 0), c.teacher_id
+        FROM groups_subjects gs
+        JOIN subjects s ON s.id = gs.subject_id
+        LEFT JOIN course_loads c ON c.group_id = gs.group_id AND c.subject_id = gs.subject_id
+        ",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get group subjects: {}", e))?;
+
+    Ok(distribute_loads(
+        sections,
+        teachers,
+        qualified,
+        keep_existing,
+    ))
+}
+
+/// Seccion por repartir (grupo, materia, horas, prioridad, profesor del plan actual)
+type Section = (i16, i16, i16, i16, Option<i16>);
+
+/// Reparte las secciones entre los profesores (ver `plan_course_loads`)
+/// # Argumentos
+/// * `sections` - Materias de los grupos
+/// * `teachers` - Profesores (id, horas comisionadas, desempeño)
+/// * `qualified` - Materias que puede impartir cada profesor (materia, profesor)
+/// * `keep_existing` - Si es verdadero se conservan las cargas ya guardadas
+fn distribute_loads(
+    mut sections: Vec<Section>,
+    mut teachers: Vec<(i16, i16, i16)>,
+    qualified: Vec<(i16, i16)>,
+    keep_existing: bool,
+) -> LoadPlan {
+    let mut by_subject: HashMap<i16, Vec<i16>> = HashMap::new();
+    for (subject_id, teacher_id) in qualified {
+        by_subject.entry(subject_id).or_default().push(teacher_id);
+    }
+
+    // Mayor prioridad primero, luego las de mas horas
+    sections.sort_by_key(|(group_id, subject_id, hours, priority, _)| {
+        (-priority, -hours, *group_id, *subject_id)
+    });
+    teachers.sort_by_key(|(id, _, _)| *id);
+
+    let mut planned: HashMap<i16, i16> = HashMap::new();
+    let mut loads: Vec<PlannedLoad> = Vec::new();
+    let mut pending: Vec<(i16, i16, i16)> = Vec::new();
+
+    // Primero se cuentan las cargas que se conservan
+    for (group_id, subject_id, hours, _, current) in sections {
+        match current {
+            Some(teacher_id) if keep_existing => {
+                *planned.entry(teacher_id).or_default() += hours;
+                loads.push(PlannedLoad {
+                    group_id,
+                    subject_id,
+                    hours,
+                    teacher_id: Some(teacher_id),
+                    existing: true,
+                });
+            }
+            _ => pending.push((group_id, subject_id, hours)),
+        }
+    }
+
+    for (group_id, subject_id, hours) in pending {
+        let candidates = by_subject.get(&subject_id).cloned().unwrap_or_default();
+        let teacher_id = teachers
+            .iter()
+            .filter(|(id, _, _)| candidates.contains(id))
+            .max_by_key(|(id, commisioned, performance)| {
+                let free = commisioned - planned.get(id).copied().unwrap_or(0);
+                (free >= hours, free, *performance, -id)
+            })
+            .map(|(id, _, _)| *id);
+
+        if let Some(id) = teacher_id {
+            *planned.entry(id).or_default() += hours;
+        }
+        loads.push(PlannedLoad {
+            group_id,
+            subject_id,
+            hours,
+            teacher_id,
+            existing: false,
+        });
+    }
+
+    let teachers = teachers
+        .into_iter()
+        .map(|(teacher_id, commisioned_hours, _)| TeacherLoadSummary {
+            teacher_id,
+            commisioned_hours,
+            planned_hours: planned.get(&teacher_id).copied().unwrap_or(0),
+        })
+        .collect();
+
+    LoadPlan { loads, teachers }
+}
+
+/// Funcion para guardar una propuesta de reparto de carga ya revisada
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `loads` - Cargas a guardar (las de la propuesta que tienen profesor)
+/// Reemplaza el profesor de las secciones que ya tenian carga
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn apply_course_load_plan(
+    pool: tauri::State<'_, AppState>,
+    loads: Vec<CourseLoad>,
+) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    for load in loads {
+        sqlx::query(
+            "
+            INSERT INTO course_loads (group_id, subject_id, teacher_id)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT (group_id, subject_id) DO UPDATE
+            SET teacher_id = excluded.teacher_id
+            ",
+        )
+        .bind(load.group_id)
+        .bind(load.subject_id)
+        .bind(load.teacher_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to save course load: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assigned(plan: &LoadPlan) -> Vec<(i16, i16, Option<i16>)> {
+        plan.loads
+            .iter()
+            .map(|l| (l.group_id, l.subject_id, l.teacher_id))
+            .collect()
+    }
+
+    #[test]
+    fn ties_go_to_better_performance_then_lowest_id() {
+        let teachers = vec![(3, 10, 5), (2, 10, 5), (1, 10, 4)];
+        let qualified = vec![(1, 1), (1, 2), (1, 3)];
+
+        let plan = distribute_loads(vec![(1, 1, 4, 0, None)], teachers, qualified, false);
+        assert_eq!(assigned(&plan), vec![(1, 1, Some(2))]);
+    }
+
+    #[test]
+    fn higher_priority_sections_pick_first_and_free_hours_decide() {
+        let teachers = vec![(1, 6, 0), (2, 4, 9)];
+        let qualified = vec![(1, 1), (1, 2), (2, 1), (2, 2)];
+        let sections = vec![(1, 1, 4, 0, None), (1, 2, 4, 5, None)];
+
+        let plan = distribute_loads(sections, teachers, qualified, false);
+        // La materia 2 va primero y se la lleva el de mas horas libres
+        assert_eq!(assigned(&plan), vec![(1, 2, Some(1)), (1, 1, Some(2))]);
+    }
+
+    #[test]
+    fn overload_goes_to_the_teacher_that_exceeds_the_least() {
+        let teachers = vec![(1, 2, 9), (2, 3, 0)];
+        let qualified = vec![(1, 1), (1, 2)];
+
+        let plan = distribute_loads(vec![(1, 1, 5, 0, None)], teachers, qualified, false);
+        assert_eq!(assigned(&plan), vec![(1, 1, Some(2))]);
+        assert_eq!(plan.teachers[1].planned_hours, 5);
+        assert_eq!(plan.teachers[0].planned_hours, 0);
+    }
+
+    #[test]
+    fn sections_without_qualified_teacher_stay_empty() {
+        let teachers = vec![(1, 10, 0)];
+        let qualified = vec![(1, 1)];
+        let sections = vec![(1, 2, 3, 0, None), (2, 2, 3, 0, Some(1))];
+
+        let plan = distribute_loads(sections, teachers, qualified, true);
+        assert_eq!(assigned(&plan), vec![(2, 2, Some(1)), (1, 2, None)]);
+        assert!(plan.loads[0].existing);
+        assert_eq!(plan.teachers[0].planned_hours, 3);
+    }
+}
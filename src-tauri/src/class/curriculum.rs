@@ -0,0 +1,398 @@
+use crate::db::AppState;
+use crate::util::assignments::delete_empty_combined;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::{Executor, Sqlite, SqliteConnection};
+use std::collections::BTreeMap;
+
+/// Materia dentro de un plan de estudios (o ligada a un grupo)
+/// `hours` son los modulos por semana, si es nulo se usan los `required_modules` de la materia
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct TemplateSubject {
+    pub subject_id: i16,
+    pub hours: Option<i16>,
+    #[serde(default)]
+    pub elective: bool,
+}
+
+/// Estructura de un plan de estudios
+/// Define las materias que lleva un grupo segun su carrera y grado
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurriculumTemplate {
+    pub id: Option<i16>,
+    pub career: Option<String>,
+    pub grade: i16,
+    pub subjects: Vec<TemplateSubject>,
+}
+
+/// Diferencias entre un plan de estudios y las materias ligadas a un grupo
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CurriculumDiff {
+    pub group_id: i16,
+    /// Materias del plan que el grupo no tiene
+    pub added: Vec<TemplateSubject>,
+    /// Materias del grupo que ya no estan en el plan
+    pub removed: Vec<TemplateSubject>,
+    /// Materias con horas u optativa distintas (con los valores del plan)
+    pub changed: Vec<TemplateSubject>,
+    /// Modulos del horario que se borrarian al quitar materias
+    pub removed_assignments: i16,
+}
+
+impl CurriculumDiff {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Funcion para guardar un plan de estudios
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `template` - Plan de estudios (carrera, grado y materias)
+/// Si ya existe un plan para la carrera y grado se reemplazan sus materias
+/// Retorna el ID del plan si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn save_curriculum_template(
+    pool: tauri::State<'_, AppState>,
+    template: CurriculumTemplate,
+) -> Result<i16, String> {
+    let career = template.career.unwrap_or_default().trim().to_string();
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let template_id: i16 = sqlx::query_scalar(
+        "
+        INSERT INTO curriculum_templates (career, grade) VALUES (?1, ?2)
+        ON CONFLICT (career, grade) DO UPDATE SET grade = excluded.grade
+        RETURNING id
+        ",
+    )
+    .bind(&career)
+    .bind(template.grade)
+    .fetch_one(&mut tx)
+    .await
+    .map_err(|e| format!("Failed to save curriculum template: {}", e))?;
+
+    sqlx::query("DELETE FROM curriculum_subjects WHERE template_id = ?1")
+        .bind(template_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to delete curriculum subjects: {}", e))?;
+
+    for subject in template.subjects {
+        sqlx::query(
+            "
+            INSERT OR REPLACE INTO curriculum_subjects (template_id, subject_id, hours, elective)
+            VALUES (?1, ?2, ?3, ?4)
+            ",
+        )
+        .bind(template_id)
+        .bind(subject.subject_id)
+        .bind(subject.hours)
+        .bind(subject.elective)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to attach subject to curriculum: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(template_id)
+}
+
+/// Funcion para obtener todos los planes de estudio
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Retorna un vector con los planes y sus materias
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_curriculum_templates(
+    pool: tauri::State<'_, AppState>,
+) -> Result<Vec<CurriculumTemplate>, String> {
+    let templates: Vec<(i16, String, i16)> =
+        sqlx::query_as("SELECT id, career, grade FROM curriculum_templates ORDER BY career, grade")
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get curriculum templates: {}", e))?;
+
+    let mut result: Vec<CurriculumTemplate> = Vec::new();
+    for (id, career, grade) in templates {
+        result.push(CurriculumTemplate {
+            id: Some(id),
+            career: if career.is_empty() {
+                None
+            } else {
+                Some(career)
+            },
+            grade,
+            subjects: template_subjects(&pool.db, id).await?,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Funcion para eliminar un plan de estudios
+/// Los grupos conservan las materias que ya tenian ligadas
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_curriculum_template(
+    pool: tauri::State<'_, AppState>,
+    id: i16,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM curriculum_subjects WHERE template_id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete curriculum subjects: {}", e))?;
+
+    sqlx::query("DELETE FROM curriculum_templates WHERE id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete curriculum template: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para ver que cambiaria en los grupos existentes al aplicar un plan de estudios
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `template_id` - ID del plan
+/// Retorna las diferencias de cada grupo de esa carrera y grado (solo los que cambian)
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn preview_curriculum_template(
+    pool: tauri::State<'_, AppState>,
+    template_id: i16,
+) -> Result<Vec<CurriculumDiff>, String> {
+    let mut conn = pool
+        .db
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to get a connection: {}", e))?;
+
+    curriculum_diffs(&mut conn, template_id).await
+}
+
+/// Funcion para aplicar un plan de estudios a los grupos existentes
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `template_id` - ID del plan
+/// Agrega y actualiza las materias del plan, quita las que ya no estan junto con sus
+/// modulos en el horario, su carga academica y las optativas elegidas por los alumnos
+/// del grupo. Retorna las diferencias que se aplicaron
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn apply_curriculum_template(
+    pool: tauri::State<'_, AppState>,
+    template_id: i16,
+) -> Result<Vec<CurriculumDiff>, String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let diffs = curriculum_diffs(&mut tx, template_id).await?;
+
+    for diff in &diffs {
+        for subject in &diff.removed {
+            sqlx::query("DELETE FROM groups_subjects WHERE group_id = ?1 AND subject_id = ?2")
+                .bind(diff.group_id)
+                .bind(subject.subject_id)
+                .execute(&mut tx)
+                .await
+                .map_err(|e| format!("Failed to delete group subject: {}", e))?;
+
+            sqlx::query("DELETE FROM assignments WHERE group_id = ?1 AND subject_id = ?2")
+                .bind(diff.group_id)
+                .bind(subject.subject_id)
+                .execute(&mut tx)
+                .await
+                .map_err(|e| format!("Failed to delete group assignments: {}", e))?;
+
+            sqlx::query("DELETE FROM course_loads WHERE group_id = ?1 AND subject_id = ?2")
+                .bind(diff.group_id)
+                .bind(subject.subject_id)
+                .execute(&mut tx)
+                .await
+                .map_err(|e| format!("Failed to delete course load: {}", e))?;
+
+            sqlx::query(
+                "
+                DELETE FROM student_electives
+                WHERE subject_id = ?2
+                AND student_id IN (SELECT id FROM students WHERE group_id = ?1)
+                ",
+            )
+            .bind(diff.group_id)
+            .bind(subject.subject_id)
+            .execute(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to delete student electives: {}", e))?;
+        }
+
+        for subject in diff.added.iter().chain(diff.changed.iter()) {
+            sqlx::query(
+                "
+                INSERT INTO groups_subjects (group_id, subject_id, hours, elective)
+                VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (group_id, subject_id) DO UPDATE
+                SET hours = excluded.hours, elective = excluded.elective
+                ",
+            )
+            .bind(diff.group_id)
+            .bind(subject.subject_id)
+            .bind(subject.hours)
+            .bind(subject.elective)
+            .execute(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to assign subject to group: {}", e))?;
+        }
+    }
+
+    delete_empty_combined(&mut tx).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(diffs)
+}
+
+/// Liga al grupo las materias del plan de estudios de su carrera y grado
+/// Se utiliza al crear o importar grupos, no hace nada si no hay plan
+pub async fn link_curriculum<'c, E>(
+    executor: E,
+    group_id: i16,
+    career: Option<&str>,
+    grade: i16,
+) -> Result<(), String>
+where
+    E: Executor<'c, Database = Sqlite>,
+{
+    sqlx::query(
+        "
+        INSERT OR IGNORE INTO groups_subjects (group_id, subject_id, hours, elective)
+        SELECT ?1, cs.subject_id, cs.hours, cs.elective
+        FROM curriculum_subjects cs
+        JOIN curriculum_templates t ON t.id = cs.template_id
+        WHERE t.career = TRIM(COALESCE(?2, '')) AND t.grade = ?3
+        ",
+    )
+    .bind(group_id)
+    .bind(career)
+    .bind(grade)
+    .execute(executor)
+    .await
+    .map_err(|e| format!("Failed to link curriculum subjects to group: {}", e))?;
+
+    Ok(())
+}
+
+/// Obtiene las materias de un plan de estudios
+async fn template_subjects<'c, E>(
+    executor: E,
+    template_id: i16,
+) -> Result<Vec<TemplateSubject>, String>
+where
+    E: Executor<'c, Database = Sqlite>,
+{
+    sqlx::query_as::<_, TemplateSubject>(
+        "SELECT subject_id, hours, elective FROM curriculum_subjects WHERE template_id = ?1",
+    )
+    .bind(template_id)
+    .fetch_all(executor)
+    .await
+    .map_err(|e| format!("Failed to get curriculum subjects: {}", e))
+}
+
+/// Compara el plan de estudios contra cada grupo de su carrera y grado
+/// Se usa la misma conexion para que al aplicar el plan se lea dentro de la transaccion
+async fn curriculum_diffs(
+    conn: &mut SqliteConnection,
+    template_id: i16,
+) -> Result<Vec<CurriculumDiff>, String> {
+    let (career, grade): (String, i16) =
+        sqlx::query_as("SELECT career, grade FROM curriculum_templates WHERE id = ?1")
+            .bind(template_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to get curriculum template: {}", e))?
+            .ok_or_else(|| format!("Curriculum template {} not found", template_id))?;
+
+    let template: BTreeMap<i16, TemplateSubject> = template_subjects(&mut *conn, template_id)
+        .await?
+        .into_iter()
+        .map(|s| (s.subject_id, s))
+        .collect();
+
+    let group_ids: Vec<i16> = sqlx::query_scalar(
+        "SELECT id FROM groups WHERE TRIM(COALESCE(career, '')) = ?1 AND grade = ?2 ORDER BY id",
+    )
+    .bind(&career)
+    .bind(grade)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to get curriculum groups: {}", e))?;
+
+    let mut diffs: Vec<CurriculumDiff> = Vec::new();
+    for group_id in group_ids {
+        let current: BTreeMap<i16, TemplateSubject> = sqlx::query_as::<_, TemplateSubject>(
+            "SELECT subject_id, hours, elective FROM groups_subjects WHERE group_id = ?1",
+        )
+        .bind(group_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to get group subjects: {}", e))?
+        .into_iter()
+        .map(|s| (s.subject_id, s))
+        .collect();
+
+        let mut diff = CurriculumDiff {
+            group_id,
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+            removed_assignments: 0,
+        };
+
+        for (subject_id, subject) in &template {
+            match current.get(subject_id) {
+                None => diff.added.push(subject.clone()),
+                Some(linked) if linked != subject => diff.changed.push(subject.clone()),
+                Some(_) => {}
+            }
+        }
+
+        for (subject_id, subject) in &current {
+            if !template.contains_key(subject_id) {
+                diff.removed.push(subject.clone());
+
+                let count: i16 = sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM assignments WHERE group_id = ?1 AND subject_id = ?2",
+                )
+                .bind(group_id)
+                .bind(subject_id)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to count group assignments: {}", e))?;
+                diff.removed_assignments += count;
+            }
+        }
+
+        if !diff.is_empty() {
+            diffs.push(diff);
+        }
+    }
+
+    Ok(diffs)
+}
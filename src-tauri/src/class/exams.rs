@@ -0,0 +1,483 @@
+use crate::class::classrooms::Classroom;
+use crate::class::school_calendar::is_calendar_slot;
+use crate::db::{AppState, DbPool};
+use crate::util::calendar::{dates_between, day_name, parse_date, SCHOOL_DAYS};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::collections::HashSet;
+
+/// Estructura de un examen
+/// `invigilator_id` es el profesor que cuida el examen
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Exam {
+    pub id: Option<i16>,
+    pub group_id: i16,
+    pub subject_id: i16,
+    pub date: String,
+    pub module_index: i16,
+    pub classroom_id: Option<i16>,
+    pub invigilator_id: Option<i16>,
+}
+
+/// Examen que no se pudo acomodar en el calendario
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnscheduledExam {
+    pub group_id: i16,
+    pub subject_id: i16,
+    pub reason: String,
+}
+
+/// Propuesta de calendario de examenes, no se guarda hasta llamar `save_exams`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExamCalendar {
+    pub exams: Vec<Exam>,
+    pub unscheduled: Vec<UnscheduledExam>,
+}
+
+/// Materia de un grupo que necesita examen
+#[derive(Debug, FromRow)]
+struct ExamSection {
+    group_id: i16,
+    subject_id: i16,
+    students: i16,
+    teacher_id: Option<i16>,
+}
+
+/// Funcion para proponer un calendario de examenes
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `start_date` - Primer dia del periodo de examenes (`YYYY-MM-DD`)
+/// * `end_date` - Ultimo dia del periodo de examenes (`YYYY-MM-DD`)
+/// * `modules` - Modulos del dia en los que pueden empezar examenes (ej: [0, 3]), deben estar
+///   dentro del calendario escolar
+/// Cada materia de `groups_subjects` lleva un examen. Un grupo tiene a lo mas un examen
+/// por dia, el salon debe tener cupo para los alumnos del grupo y el profesor que cuida
+/// no puede tener otro examen al mismo tiempo, ni estar marcado como no disponible o con falta.
+/// Se prefiere que cuide el profesor de la materia
+/// No guarda nada, retorna la propuesta para revisarla
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn plan_exams(
+    pool: tauri::State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    modules: Vec<i16>,
+) -> Result<ExamCalendar, String> {
+    let start = parse_date(&start_date)?;
+    let end = parse_date(&end_date)?;
+    if end < start {
+        return Err("The exam period end date is before its start date".to_string());
+    }
+    if modules.is_empty() {
+        return Err("At least one exam module is needed".to_string());
+    }
+    for module_index in &modules {
+        check_exam_module(&pool.db, *module_index).await?;
+    }
+
+    let dates: Vec<(String, &str)> = dates_between(start, end)
+        .into_iter()
+        .map(|d| (d.to_string(), day_name(d)))
+        .filter(|(_, day)| SCHOOL_DAYS.contains(day))
+        .collect();
+
+    // Los grupos grandes primero para que alcancen los salones grandes
+    let sections: Vec<ExamSection> = sqlx::query_as::<_, ExamSection>(
+        "
+        SELECT gs.group_id, gs.subject_id, COALESCE(g.students, 0) AS students,
+               COALESCE(
+                   (SELECT c.teacher_id FROM course_loads c
+                    WHERE c.group_id = gs.group_id AND c.subject_id = gs.subject_id),
+                   (SELECT a.teacher_id FROM assignments a
+                    WHERE a.group_id = gs.group_id AND a.subject_id = gs.subject_id
+                    GROUP BY a.teacher_id ORDER BY COUNT(*) DESC LIMIT 1)
+               ) AS teacher_id
+        FROM groups_subjects gs
+        JOIN groups g ON g.id = gs.group_id
+        ORDER BY students DESC, gs.group_id, gs.subject_id
+        ",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get group subjects: {}", e))?;
+
+    // Salones de menor a mayor cupo para no gastar los grandes en grupos chicos
+    let mut classrooms: Vec<Classroom> = sqlx::query_as::<_, Classroom>("SELECT * FROM classroom")
+        .fetch_all(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to get classrooms: {}", e))?;
+    classrooms.sort_by_key(|c| c.capacity.unwrap_or(0));
+
+    let teachers: Vec<i16> = sqlx::query_scalar("SELECT id FROM teachers ORDER BY id")
+        .fetch_all(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to get teachers: {}", e))?;
+
+    let unavailable: HashSet<(i16, String, i16)> =
+        sqlx::query_as("SELECT teacher_id, day, module_index FROM teacher_unavailability")
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get teacher unavailability: {}", e))?
+            .into_iter()
+            .collect();
+
+    let absences: Vec<(i16, String, String)> =
+        sqlx::query_as("SELECT teacher_id, start_date, end_date FROM teacher_absences")
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get teacher absences: {}", e))?;
+
+    Ok(place_exams(
+        sections,
+        &dates,
+        &modules,
+        &classrooms,
+        &teachers,
+        &unavailable,
+        &absences,
+    ))
+}
+
+/// Revisa que un modulo de examen este dentro del calendario escolar
+async fn check_exam_module(pool: &DbPool, module_index: i16) -> Result<(), String> {
+    if !is_calendar_slot(pool, SCHOOL_DAYS[0], module_index).await? {
+        return Err(format!(
+            "Exam module {} is outside the school calendar",
+            module_index
+        ));
+    }
+
+    Ok(())
+}
+
+/// Acomoda los examenes en las fechas y modulos del periodo (ver `plan_exams`)
+/// # Argumentos
+/// * `sections` - Materias de los grupos, en el orden en que se acomodan
+/// * `dates` - Fechas del periodo con su dia de la semana
+/// * `modules` - Modulos en los que pueden empezar examenes
+/// * `classrooms` - Salones de menor a mayor cupo
+/// * `teachers` - Profesores que pueden cuidar
+/// * `unavailable` - Modulos no disponibles de cada profesor (profesor, dia, modulo)
+/// * `absences` - Faltas de los profesores (profesor, fecha inicial, fecha final)
+fn place_exams(
+    sections: Vec<ExamSection>,
+    dates: &[(String, &str)],
+    modules: &[i16],
+    classrooms: &[Classroom],
+    teachers: &[i16],
+    unavailable: &HashSet<(i16, String, i16)>,
+    absences: &[(i16, String, String)],
+) -> ExamCalendar {
+    let mut exams: Vec<Exam> = Vec::new();
+    let mut unscheduled: Vec<UnscheduledExam> = Vec::new();
+
+    for section in sections {
+        let mut placed = false;
+        let mut reason = "No free day left for the group".to_string();
+
+        'dates: for (date, day) in dates {
+            if exams
+                .iter()
+                .any(|e| e.group_id == section.group_id && e.date == *date)
+            {
+                continue;
+            }
+
+            for module_index in modules {
+                let taken = |e: &&Exam| e.date == *date && e.module_index == *module_index;
+
+                let Some(classroom_id) = classrooms
+                    .iter()
+                    .filter(|c| c.capacity.unwrap_or(0) >= section.students)
+                    .filter(|c| {
+                        !c.availability
+                            .iter()
+                            .any(|b| b.day == *day && b.module_index == *module_index)
+                    })
+                    .filter_map(|c| c.id)
+                    .find(|id| {
+                        !exams
+                            .iter()
+                            .filter(taken)
+                            .any(|e| e.classroom_id == Some(*id))
+                    })
+                else {
+                    reason = format!("No free classroom for {} students", section.students);
+                    continue;
+                };
+
+                // El profesor de la materia primero y despues el que menos examenes cuida
+                let mut candidates: Vec<i16> = teachers.to_vec();
+                candidates.sort_by_key(|t| {
+                    let duties = exams
+                        .iter()
+                        .filter(|e| e.invigilator_id == Some(*t))
+                        .count();
+                    (section.teacher_id != Some(*t), duties)
+                });
+                let invigilator = candidates.into_iter().find(|t| {
+                    !exams
+                        .iter()
+                        .filter(taken)
+                        .any(|e| e.invigilator_id == Some(*t))
+                        && !unavailable.contains(&(*t, day.to_string(), *module_index))
+                        && !absences
+                            .iter()
+                            .any(|(a, s, e)| a == t && s <= date && date <= e)
+                });
+                let Some(invigilator) = invigilator else {
+                    reason = "No free teacher to invigilate".to_string();
+                    continue;
+                };
+
+                exams.push(Exam {
+                    id: None,
+                    group_id: section.group_id,
+                    subject_id: section.subject_id,
+                    date: date.clone(),
+                    module_index: *module_index,
+                    classroom_id: Some(classroom_id),
+                    invigilator_id: Some(invigilator),
+                });
+                placed = true;
+                break 'dates;
+            }
+        }
+
+        if !placed {
+            unscheduled.push(UnscheduledExam {
+                group_id: section.group_id,
+                subject_id: section.subject_id,
+                reason,
+            });
+        }
+    }
+
+    ExamCalendar { exams, unscheduled }
+}
+
+/// Funcion para guardar un calendario de examenes ya revisado
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `start_date` - Primer dia del periodo de examenes
+/// * `end_date` - Ultimo dia del periodo de examenes
+/// * `exams` - Examenes a guardar
+/// Reemplaza los examenes que ya habia en el periodo
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn save_exams(
+    pool: tauri::State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    exams: Vec<Exam>,
+) -> Result<(), String> {
+    let start = parse_date(&start_date)?.to_string();
+    let end = parse_date(&end_date)?.to_string();
+    for exam in &exams {
+        check_exam_module(&pool.db, exam.module_index).await?;
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    sqlx::query("DELETE FROM exams WHERE date BETWEEN ?1 AND ?2")
+        .bind(&start)
+        .bind(&end)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to clear exams: {}", e))?;
+
+    for exam in exams {
+        let date = parse_date(&exam.date)?.to_string();
+        if date < start || date > end {
+            return Err(format!("Exam date {} is outside the exam period", date));
+        }
+
+        sqlx::query(
+            "
+            INSERT INTO exams
+                (group_id, subject_id, date, module_index, classroom_id, invigilator_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ",
+        )
+        .bind(exam.group_id)
+        .bind(exam.subject_id)
+        .bind(&date)
+        .bind(exam.module_index)
+        .bind(exam.classroom_id)
+        .bind(exam.invigilator_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to save exam of group {} on {}: {}",
+                exam.group_id, date, e
+            )
+        })?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para obtener los examenes
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo (opcional, si es nulo regresa los de todos los grupos)
+/// Retorna un vector con los examenes ordenados por fecha
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_exams(
+    pool: tauri::State<'_, AppState>,
+    group_id: Option<i16>,
+) -> Result<Vec<Exam>, String> {
+    let exams: Vec<Exam> = sqlx::query_as::<_, Exam>(
+        "
+        SELECT * FROM exams
+        WHERE ?1 IS NULL OR group_id = ?1
+        ORDER BY date, module_index, group_id
+        ",
+    )
+    .bind(group_id)
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get exams: {}", e))?;
+
+    Ok(exams)
+}
+
+/// Funcion para eliminar un examen
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del examen
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_exam(pool: tauri::State<'_, AppState>, id: i16) -> Result<(), String> {
+    sqlx::query("DELETE FROM exams WHERE id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete exam: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class::classrooms::AvailabilityBlock;
+
+    fn section(group_id: i16, subject_id: i16, students: i16, teacher_id: i16) -> ExamSection {
+        ExamSection {
+            group_id,
+            subject_id,
+            students,
+            teacher_id: Some(teacher_id),
+        }
+    }
+
+    fn classroom(id: i16, capacity: i16) -> Classroom {
+        Classroom {
+            id: Some(id),
+            building_id: None,
+            building_number: id,
+            building_type: None,
+            capacity: Some(capacity),
+            availability: Vec::new(),
+        }
+    }
+
+    fn dates() -> Vec<(String, &'static str)> {
+        vec![
+            ("2025-06-02".to_string(), "Lunes"),
+            ("2025-06-03".to_string(), "Martes"),
+        ]
+    }
+
+    #[test]
+    fn a_group_has_one_exam_per_day() {
+        let calendar = place_exams(
+            vec![section(1, 1, 20, 1), section(1, 2, 20, 2)],
+            &dates(),
+            &[0, 3],
+            &[classroom(1, 30)],
+            &[1, 2],
+            &HashSet::new(),
+            &[],
+        );
+
+        assert!(calendar.unscheduled.is_empty());
+        let placed: Vec<(&str, i16, Option<i16>)> = calendar
+            .exams
+            .iter()
+            .map(|e| (e.date.as_str(), e.module_index, e.invigilator_id))
+            .collect();
+        assert_eq!(
+            placed,
+            vec![("2025-06-02", 0, Some(1)), ("2025-06-03", 0, Some(2))]
+        );
+    }
+
+    #[test]
+    fn classrooms_need_capacity_and_availability() {
+        let mut blocked = classroom(1, 40);
+        blocked.availability.push(AvailabilityBlock {
+            day: "Lunes".to_string(),
+            module_index: 0,
+        });
+
+        let calendar = place_exams(
+            vec![section(1, 1, 35, 1), section(2, 1, 50, 1)],
+            &dates()[..1],
+            &[0, 3],
+            &[classroom(2, 30), blocked],
+            &[1],
+            &HashSet::new(),
+            &[],
+        );
+
+        assert_eq!(calendar.exams.len(), 1);
+        assert_eq!(calendar.exams[0].module_index, 3);
+        assert_eq!(calendar.exams[0].classroom_id, Some(1));
+        assert_eq!(calendar.unscheduled.len(), 1);
+        assert_eq!(calendar.unscheduled[0].group_id, 2);
+        assert_eq!(
+            calendar.unscheduled[0].reason,
+            "No free classroom for 50 students"
+        );
+    }
+
+    #[test]
+    fn invigilators_skip_busy_unavailable_and_absent_teachers() {
+        // El profesor 1 da la materia pero no esta disponible el lunes en el modulo 0
+        let unavailable = HashSet::from([(1, "Lunes".to_string(), 0)]);
+        // El profesor 2 falta el lunes
+        let absences = vec![(2, "2025-06-02".to_string(), "2025-06-02".to_string())];
+
+        let calendar = place_exams(
+            vec![section(1, 1, 20, 1), section(2, 1, 20, 1)],
+            &dates()[..1],
+            &[0],
+            &[classroom(1, 30), classroom(2, 30)],
+            &[1, 2, 3],
+            &unavailable,
+            &absences,
+        );
+
+        assert_eq!(calendar.exams.len(), 1);
+        assert_eq!(calendar.exams[0].invigilator_id, Some(3));
+        assert_eq!(calendar.unscheduled.len(), 1);
+        assert_eq!(
+            calendar.unscheduled[0].reason,
+            "No free teacher to invigilate"
+        );
+    }
+}
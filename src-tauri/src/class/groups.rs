@@ -3,8 +3,11 @@ use futures::TryStreamExt; // Para poder usar try_next() en los streams
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use sqlx::Row;
+use std::collections::HashMap;
 
+use crate::class::curriculum::link_curriculum;
 use crate::class::subjects::Subject;
+use crate::util::assignments::delete_empty_combined;
 
 /// Estructura de un grupo
 /// Se utiliza para mapear los datos del grupo de la base de datos a un objeto en Rust
@@ -17,6 +20,17 @@ pub struct Group {
     pub students: Option<i16>,
 }
 
+/// Estructura de un subgrupo
+/// Un grupo se puede dividir (ej: mitades para laboratorio o idiomas) y cada parte
+/// puede tener una clase distinta en el mismo modulo
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Subgroup {
+    pub id: Option<i16>,
+    pub group_id: i16,
+    pub name: String,
+    pub students: Option<i16>,
+}
+
 /// Funcion para crear un grupo
 /// # Argumentos
 /// * `pool` - Conexion a la base de datos
@@ -44,12 +58,18 @@ pub async fn create_group(
     )
     .bind(grade)
     .bind(group)
-    .bind(career)
+    .bind(&career)
     .bind(students)
     .fetch_one(&pool.db)
     .await
     .map_err(|e| format!("Failed to create group, error: {}", e))?;
 
+    // Sin materias explicitas se ligan las del plan de estudios de la carrera y grado
+    let subjects = subjects.filter(|s| !s.is_empty());
+    if subjects.is_none() {
+        link_curriculum(&pool.db, group_id, career.as_deref(), grade).await?;
+    }
+
     if let Some(subjects) = subjects {
         for subject in subjects {
             // Checa si existe la materia en el grupo, regresa el id si se encuentra
@@ -97,16 +117,19 @@ pub async fn create_groups(
         .map_err(|e| format!("Failed to start transaction! {}", e))?;
 
     for g in groups {
-        sqlx::query(
-            r#"INSERT INTO groups(grade, "group", career, students) VALUES (?1, ?2, ?3, ?4)"#,
+        let group_id: i16 = sqlx::query_scalar(
+            r#"INSERT INTO groups(grade, "group", career, students) VALUES (?1, ?2, ?3, ?4) RETURNING id"#,
         )
         .bind(g.grade)
         .bind(g.group)
-        .bind(g.career)
+        .bind(&g.career)
         .bind(g.students)
-        .execute(&mut tx)
+        .fetch_one(&mut tx)
         .await
         .map_err(|e| format!("Error creating the group, error: {}", e))?;
+
+        // Ligar las materias del plan de estudios de la carrera y grado
+        link_curriculum(&mut tx, group_id, g.career.as_deref(), g.grade).await?;
     }
 
     tx.commit()
@@ -172,18 +195,30 @@ pub async fn get_groups(
 #[allow(dead_code, unused)]
 #[tauri::command]
 pub async fn delete_group(pool: tauri::State<'_, AppState>, id: i16) -> Result<(), String> {
-    sqlx::query("DELETE FROM groups WHERE id = ?1")
-        .bind(id)
-        .execute(&pool.db)
+    // Examenes, cargas, subgrupos y alumnos se limpian con ON DELETE CASCADE / SET NULL
+    let mut tx = pool
+        .db
+        .begin()
         .await
-        .map_err(|e| format!("Failed to delete group: {}", e))?;
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
 
     // Borrar asignaciones de horario ligadas al grupo
     sqlx::query("DELETE FROM assignments WHERE group_id = ?1")
         .bind(id)
-        .execute(&pool.db)
+        .execute(&mut tx)
         .await
         .map_err(|e| format!("Failed to delete group assignment: {}", e))?;
+    delete_empty_combined(&mut tx).await?;
+
+    sqlx::query("DELETE FROM groups WHERE id = ?1")
+        .bind(id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to delete group: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
 
     Ok(())
 }
@@ -225,6 +260,13 @@ pub async fn update_group(
     students: Option<i16>,
     subjects: Option<Vec<Subject>>,
 ) -> Result<(), String> {
+    // El grupo y sus materias se guardan juntos, si algo falla el grupo no se queda sin materias
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
     sqlx::query(
         r#"UPDATE groups SET grade = ?1, "group" = ?2, career = ?3, students = ?4 WHERE id = ?5"#,
     )
@@ -233,27 +275,146 @@ pub async fn update_group(
     .bind(career)
     .bind(students)
     .bind(id)
-    .execute(&pool.db)
+    .execute(&mut tx)
     .await
     .map_err(|e| format!("Failed to update group: {}", e))?;
 
     if let Some(subjects) = subjects {
+        // Guardar las optativas y las horas (del plan de estudios) para no perderlas al volver
+        // a ligar las materias: materia -> (optativa, horas)
+        let previous: HashMap<i16, (bool, Option<i16>)> =
+            sqlx::query_as::<_, (i16, bool, Option<i16>)>(
+                "SELECT subject_id, elective, hours FROM groups_subjects WHERE group_id = ?1",
+            )
+            .bind(id)
+            .fetch_all(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to get group subjects: {}", e))?
+            .into_iter()
+            .map(|(subject_id, elective, hours)| (subject_id, (elective, hours)))
+            .collect();
+
         // Eliminar las materias del grupo si existian
         sqlx::query("DELETE FROM groups_subjects WHERE group_id = ?1")
             .bind(id)
-            .execute(&pool.db)
+            .execute(&mut tx)
             .await
             .map_err(|e| format!("Failed to delete group subject: {}", e))?;
         for subject in subjects {
+            let (elective, hours) = subject
+                .id
+                .and_then(|s| previous.get(&s).copied())
+                .unwrap_or((false, None));
             // Agrega materia al grupo
-            sqlx::query("INSERT INTO groups_subjects (group_id, subject_id) VALUES (?1, ?2)")
-                .bind(id)
-                .bind(subject.id)
-                .fetch_optional(&pool.db)
-                .await
-                .map_err(|e| format!("Failed to assign the subject to existed group: {}", e))?;
+            sqlx::query(
+                "
+                INSERT INTO groups_subjects (group_id, subject_id, elective, hours)
+                VALUES (?1, ?2, ?3, ?4)
+                ",
+            )
+            .bind(id)
+            .bind(subject.id)
+            .bind(elective)
+            .bind(hours)
+            .execute(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to assign the subject to existed group: {}", e))?;
         }
     }
 
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para crear un subgrupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `subgroup` - Subgrupo (grupo al que pertenece, nombre y alumnos)
+/// Retorna el ID del subgrupo si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn create_subgroup(
+    pool: tauri::State<'_, AppState>,
+    subgroup: Subgroup,
+) -> Result<i16, String> {
+    let id: i16 = sqlx::query_scalar(
+        "INSERT INTO subgroups (group_id, name, students) VALUES (?1, ?2, ?3) RETURNING id",
+    )
+    .bind(subgroup.group_id)
+    .bind(subgroup.name)
+    .bind(subgroup.students)
+    .fetch_one(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to create subgroup: {}", e))?;
+
+    Ok(id)
+}
+
+/// Funcion para obtener los subgrupos de un grupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo
+/// Retorna un vector con los subgrupos
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_subgroups(
+    pool: tauri::State<'_, AppState>,
+    group_id: i16,
+) -> Result<Vec<Subgroup>, String> {
+    let subgroups: Vec<Subgroup> =
+        sqlx::query_as::<_, Subgroup>("SELECT * FROM subgroups WHERE group_id = ?1 ORDER BY name")
+            .bind(group_id)
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get subgroups: {}", e))?;
+
+    Ok(subgroups)
+}
+
+/// Funcion para actualizar un subgrupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `subgroup` - Subgrupo con el ID a actualizar
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn update_subgroup(
+    pool: tauri::State<'_, AppState>,
+    subgroup: Subgroup,
+) -> Result<(), String> {
+    sqlx::query("UPDATE subgroups SET name = ?1, students = ?2 WHERE id = ?3")
+        .bind(subgroup.name)
+        .bind(subgroup.students)
+        .bind(subgroup.id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to update subgroup: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para eliminar un subgrupo (y sus asignaciones)
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del subgrupo
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_subgroup(pool: tauri::State<'_, AppState>, id: i16) -> Result<(), String> {
+    sqlx::query("DELETE FROM assignments WHERE subgroup_id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete subgroup assignments: {}", e))?;
+
+    sqlx::query("DELETE FROM subgroups WHERE id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete subgroup: {}", e))?;
+
     Ok(())
 }
@@ -2,3 +2,9 @@ pub mod subjects;
 pub mod teachers;
 pub mod groups;
 pub mod classrooms;
+pub mod absences;
+pub mod students;
+pub mod curriculum;
+pub mod course_loads;
+pub mod exams;
+pub mod school_calendar;
@@ -0,0 +1,160 @@
+use crate::db::{AppState, DbPool};
+use crate::util::calendar::{is_school_slot, parse_date, parse_time};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+/// Horario de un modulo del dia (`HH:MM`)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ModuleTime {
+    pub module_index: i16,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+/// Estructura del calendario escolar
+/// Fechas del periodo (`YYYY-MM-DD`) y horario de cada modulo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchoolCalendar {
+    pub term_start: String,
+    pub term_end: String,
+    /// Fecha dentro de una semana A, si es nula la primera semana del periodo es A
+    pub week_a_start: Option<String>,
+    pub modules: Vec<ModuleTime>,
+}
+
+impl SchoolCalendar {
+    /// Regresa el horario de un modulo si esta definido
+    pub fn module_time(&self, module_index: i16) -> Option<&ModuleTime> {
+        self.modules.iter().find(|m| m.module_index == module_index)
+    }
+}
+
+/// Funcion para obtener el calendario escolar
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Retorna el calendario o nulo si todavia no se define
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_school_calendar(
+    pool: tauri::State<'_, AppState>,
+) -> Result<Option<SchoolCalendar>, String> {
+    fetch_school_calendar(&pool.db).await
+}
+
+/// Funcion para guardar el calendario escolar
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `calendar` - Fechas del periodo y horario de los modulos
+/// Reemplaza el calendario y los horarios de los modulos anteriores
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn save_school_calendar(
+    pool: tauri::State<'_, AppState>,
+    calendar: SchoolCalendar,
+) -> Result<(), String> {
+    let start = parse_date(&calendar.term_start)?;
+    let end = parse_date(&calendar.term_end)?;
+    if end < start {
+        return Err("The term end date is before its start date".to_string());
+    }
+    let week_a_start = match &calendar.week_a_start {
+        Some(date) => Some(parse_date(date)?.to_string()),
+        None => None,
+    };
+    for module in &calendar.modules {
+        if parse_time(&module.end_time)? <= parse_time(&module.start_time)? {
+            return Err(format!(
+                "Module {} ends before it starts",
+                module.module_index
+            ));
+        }
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    sqlx::query(
+        "
+        INSERT INTO school_calendar (id, term_start, term_end, week_a_start)
+        VALUES (1, ?1, ?2, ?3)
+        ON CONFLICT (id) DO UPDATE
+        SET term_start = excluded.term_start, term_end = excluded.term_end,
+            week_a_start = excluded.week_a_start
+        ",
+    )
+    .bind(start.to_string())
+    .bind(end.to_string())
+    .bind(week_a_start)
+    .execute(&mut tx)
+    .await
+    .map_err(|e| format!("Failed to save school calendar: {}", e))?;
+
+    sqlx::query("DELETE FROM module_times")
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to clear module times: {}", e))?;
+
+    for module in calendar.modules {
+        sqlx::query(
+            "INSERT INTO module_times (module_index, start_time, end_time) VALUES (?1, ?2, ?3)",
+        )
+        .bind(module.module_index)
+        .bind(module.start_time.trim())
+        .bind(module.end_time.trim())
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to save module time: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Obtiene el calendario escolar, nulo si todavia no se define
+pub async fn fetch_school_calendar(pool: &DbPool) -> Result<Option<SchoolCalendar>, String> {
+    let dates: Option<(String, String, Option<String>)> = sqlx::query_as(
+        "SELECT term_start, term_end, week_a_start FROM school_calendar WHERE id = 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to get school calendar: {}", e))?;
+
+    let Some((term_start, term_end, week_a_start)) = dates else {
+        return Ok(None);
+    };
+
+    let modules: Vec<ModuleTime> =
+        sqlx::query_as::<_, ModuleTime>("SELECT * FROM module_times ORDER BY module_index")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get module times: {}", e))?;
+
+    Ok(Some(SchoolCalendar {
+        term_start,
+        term_end,
+        week_a_start,
+        modules,
+    }))
+}
+
+/// Checa si el dia y modulo estan dentro del calendario escolar
+/// Si ya se definio el horario de los modulos solo cuentan los modulos que tienen horario
+pub async fn is_calendar_slot(pool: &DbPool, day: &str, module_index: i16) -> Result<bool, String> {
+    if !is_school_slot(day, module_index) {
+        return Ok(false);
+    }
+
+    match fetch_school_calendar(pool).await? {
+        Some(calendar) if !calendar.modules.is_empty() => {
+            Ok(calendar.module_time(module_index).is_some())
+        }
+        _ => Ok(true),
+    }
+}
@@ -0,0 +1,638 @@
+use crate::db::AppState;
+use crate::util::calendar::weeks_overlap;
+use futures::TryStreamExt; // Para poder usar try_next() en los streams
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Estructura de un alumno
+/// Se utiliza para mapear los datos del alumno de la base de datos a un objeto en Rust
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Student {
+    pub id: Option<i16>,
+    pub name: String,
+    pub father_lastname: String,
+    pub mother_lastname: Option<String>,
+    pub enrollment: Option<String>, // Matricula
+    pub email: Option<String>,
+    pub group_id: Option<i16>, // Grupo en el que esta inscrito
+}
+
+/// Estructura de un alumno importado desde excel
+/// El grupo se indica con el grado y la letra (ej: 1 y "A") en lugar del ID
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StudentImport {
+    pub name: String,
+    pub father_lastname: String,
+    pub mother_lastname: Option<String>,
+    pub enrollment: Option<String>,
+    pub email: Option<String>,
+    pub grade: Option<i16>,
+    pub group: Option<String>,
+}
+
+/// Choque en el horario de un alumno
+/// Dos o mas materias que debe tomar el alumno caen en el mismo modulo
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StudentCollision {
+    pub student_id: i16,
+    pub day: String,
+    pub module_index: i16,
+    pub subject_ids: Vec<i16>,
+}
+
+/// Funcion para crear un alumno
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `student` - Alumno
+/// Retorna el ID del alumno si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn create_student(
+    pool: tauri::State<'_, AppState>,
+    student: Student,
+) -> Result<i16, String> {
+    let id: i16 = sqlx::query_scalar(
+        "
+        INSERT INTO students (name, father_lastname, mother_lastname, enrollment, email, group_id)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        RETURNING id
+        ",
+    )
+    .bind(student.name)
+    .bind(student.father_lastname)
+    .bind(student.mother_lastname)
+    .bind(student.enrollment)
+    .bind(student.email)
+    .bind(student.group_id)
+    .fetch_one(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to create student: {}", e))?;
+
+    Ok(id)
+}
+
+/// Funcion para crear varios alumnos a la vez
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `students` - Vector de alumnos importados desde excel
+/// Si el grado y grupo del alumno existen se inscribe en ese grupo
+/// Retorna Ok() si todo sale exitoso de lo contrario manda un mensaje con el error
+#[tauri::command]
+pub async fn create_students(
+    pool: tauri::State<'_, AppState>,
+    students: Vec<StudentImport>,
+) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    for s in students {
+        // Buscar el grupo por grado y letra
+        let group_id: Option<i16> = match (s.grade, &s.group) {
+            (Some(grade), Some(group)) => {
+                sqlx::query_scalar(r#"SELECT id FROM groups WHERE grade = ?1 AND "group" = ?2"#)
+                    .bind(grade)
+                    .bind(group.trim())
+                    .fetch_optional(&mut tx)
+                    .await
+                    .map_err(|e| format!("Error looking for the student group: {}", e))?
+            }
+            _ => None,
+        };
+
+        sqlx::query(
+            "
+            INSERT INTO students (name, father_lastname, mother_lastname, enrollment, email, group_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ",
+        )
+        .bind(s.name)
+        .bind(s.father_lastname)
+        .bind(s.mother_lastname)
+        .bind(s.enrollment)
+        .bind(s.email)
+        .bind(group_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Error creating the student, error: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para obtener los alumnos
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo (opcional, si es nulo regresa todos los alumnos)
+/// Retorna un vector con los alumnos
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_students(
+    pool: tauri::State<'_, AppState>,
+    group_id: Option<i16>,
+) -> Result<Vec<Student>, String> {
+    let students: Vec<Student> = sqlx::query_as::<_, Student>(
+        "
+        SELECT * FROM students
+        WHERE ?1 IS NULL OR group_id = ?1
+        ORDER BY father_lastname, mother_lastname, name
+        ",
+    )
+    .bind(group_id)
+    .fetch(&pool.db)
+    .try_collect()
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(students)
+}
+
+/// Funcion para actualizar un alumno
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `student` - Alumno con el ID a actualizar
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn update_student(
+    pool: tauri::State<'_, AppState>,
+    student: Student,
+) -> Result<(), String> {
+    sqlx::query(
+        "
+        UPDATE students SET
+            name = ?1,
+            father_lastname = ?2,
+            mother_lastname = ?3,
+            enrollment = ?4,
+            email = ?5,
+            group_id = ?6
+        WHERE id = ?7
+        ",
+    )
+    .bind(student.name)
+    .bind(student.father_lastname)
+    .bind(student.mother_lastname)
+    .bind(student.enrollment)
+    .bind(student.email)
+    .bind(student.group_id)
+    .bind(student.id)
+    .execute(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to update student: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para eliminar un alumno (y sus optativas)
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del alumno
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_student(pool: tauri::State<'_, AppState>, id: i16) -> Result<(), String> {
+    // Las optativas del alumno se borran en cascada
+    sqlx::query("DELETE FROM students WHERE id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete student: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para eliminar varios alumnos
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `ids` - Vector con los ID de los alumnos
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_students(
+    pool: tauri::State<'_, AppState>,
+    ids: Vec<i16>,
+) -> Result<(), String> {
+    for i in ids {
+        delete_student(pool.clone(), i).await?;
+    }
+
+    Ok(())
+}
+
+/// Funcion para inscribir varios alumnos en un grupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo
+/// * `student_ids` - Vector con los ID de los alumnos
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn enroll_students(
+    pool: tauri::State<'_, AppState>,
+    group_id: i16,
+    student_ids: Vec<i16>,
+) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    for student_id in student_ids {
+        sqlx::query("UPDATE students SET group_id = ?1 WHERE id = ?2")
+            .bind(group_id)
+            .bind(student_id)
+            .execute(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to enroll student: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para obtener las materias optativas de un alumno
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `student_id` - ID del alumno
+/// Retorna un vector con los ID de las materias
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_student_electives(
+    pool: tauri::State<'_, AppState>,
+    student_id: i16,
+) -> Result<Vec<i16>, String> {
+    let subjects: Vec<i16> =
+        sqlx::query_scalar("SELECT subject_id FROM student_electives WHERE student_id = ?1")
+            .bind(student_id)
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get student electives: {}", e))?;
+
+    Ok(subjects)
+}
+
+/// Funcion para definir las materias optativas de un alumno
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `student_id` - ID del alumno
+/// * `subjects` - Vector con los ID de las materias elegidas
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_student_electives(
+    pool: tauri::State<'_, AppState>,
+    student_id: i16,
+    subjects: Vec<i16>,
+) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    // Eliminar las optativas del alumno si existian
+    sqlx::query("DELETE FROM student_electives WHERE student_id = ?1")
+        .bind(student_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to delete student electives: {}", e))?;
+
+    for subject_id in subjects {
+        sqlx::query(
+            "INSERT OR IGNORE INTO student_electives (student_id, subject_id) VALUES (?1, ?2)",
+        )
+        .bind(student_id)
+        .bind(subject_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to attach elective to student: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para marcar una materia del grupo como optativa u obligatoria
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo
+/// * `subject_id` - ID de la materia (debe estar ligada al grupo)
+/// * `elective` - Verdadero si la materia es optativa
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_group_elective(
+    pool: tauri::State<'_, AppState>,
+    group_id: i16,
+    subject_id: i16,
+    elective: bool,
+) -> Result<(), String> {
+    let updated = sqlx::query(
+        "UPDATE groups_subjects SET elective = ?1 WHERE group_id = ?2 AND subject_id = ?3",
+    )
+    .bind(elective)
+    .bind(group_id)
+    .bind(subject_id)
+    .execute(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to update group elective: {}", e))?;
+
+    if updated.rows_affected() == 0 {
+        return Err(format!(
+            "Subject {} is not assigned to group {}",
+            subject_id, group_id
+        ));
+    }
+
+    Ok(())
+}
+
+/// Funcion para revisar que las materias de ningun alumno choquen en el horario
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Cada alumno toma las materias obligatorias de su grupo y solo las optativas que eligio.
+/// Las optativas del grupo se marcan con `set_group_elective`
+/// Una optativa que su grupo no da se toma de cualquier grupo que la tenga en el horario, y
+/// cada grupo o subgrupo que la da es una opcion: solo cuentan los modulos que tienen todas
+/// las opciones. De las obligatorias solo cuentan las clases de todo el grupo, porque no se
+/// sabe en que subgrupo esta el alumno. Las clases de semana A y B no chocan entre si
+/// Retorna un vector con los modulos donde un alumno tendria mas de una materia
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_elective_collisions(
+    pool: tauri::State<'_, AppState>,
+) -> Result<Vec<StudentCollision>, String> {
+    let students: Vec<(i16, i16)> =
+        sqlx::query_as("SELECT id, group_id FROM students WHERE group_id IS NOT NULL ORDER BY id")
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get students: {}", e))?;
+
+    let electives: Vec<(i16, i16)> =
+        sqlx::query_as("SELECT student_id, subject_id FROM student_electives")
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get student electives: {}", e))?;
+
+    let assignments: Vec<SessionRow> = sqlx::query_as(
+        "SELECT group_id, day, module_index, subject_id, subgroup_id, week FROM assignments",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get assignments: {}", e))?;
+
+    let group_electives: Vec<(i16, i16)> =
+        sqlx::query_as("SELECT group_id, subject_id FROM groups_subjects WHERE elective = 1")
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get group electives: {}", e))?;
+
+    Ok(student_collisions(
+        students,
+        electives,
+        assignments,
+        group_electives,
+    ))
+}
+
+/// Clase del horario (grupo, dia, modulo, materia, subgrupo, semana)
+type SessionRow = (i16, String, i16, i16, Option<i16>, String);
+
+/// Calcula los choques de cada alumno (ver `get_elective_collisions`)
+/// # Argumentos
+/// * `students` - Alumnos con su grupo (alumno, grupo)
+/// * `electives` - Optativas elegidas (alumno, materia)
+/// * `assignments` - Clases del horario
+/// * `group_electives` - Optativas de cada grupo (grupo, materia)
+fn student_collisions(
+    students: Vec<(i16, i16)>,
+    electives: Vec<(i16, i16)>,
+    assignments: Vec<SessionRow>,
+    group_electives: Vec<(i16, i16)>,
+) -> Vec<StudentCollision> {
+    // Optativas elegidas por cada alumno y optativas que ofrece cada grupo
+    let mut chosen: HashMap<i16, BTreeSet<i16>> = HashMap::new();
+    for (student_id, subject_id) in electives {
+        chosen.entry(student_id).or_default().insert(subject_id);
+    }
+    let mut offered: HashMap<i16, HashSet<i16>> = HashMap::new();
+    for (group_id, subject_id) in group_electives {
+        offered.entry(group_id).or_default().insert(subject_id);
+    }
+
+    // Modulos de cada materia en cada grupo: (materia, grupo) -> subgrupo -> modulos
+    let mut sessions: BTreeMap<(i16, i16), BTreeMap<Option<i16>, BTreeSet<Slot>>> = BTreeMap::new();
+    for (group_id, day, module_index, subject_id, subgroup_id, week) in assignments {
+        sessions
+            .entry((subject_id, group_id))
+            .or_default()
+            .entry(subgroup_id)
+            .or_default()
+            .insert((day, module_index, week));
+    }
+
+    let empty = HashSet::new();
+    let no_electives = BTreeSet::new();
+    let mut collisions: Vec<StudentCollision> = Vec::new();
+    for (student_id, group_id) in students {
+        let chosen = chosen.get(&student_id).unwrap_or(&no_electives);
+        let offered = offered.get(&group_id).unwrap_or(&empty);
+
+        // Modulos fijos de cada materia que toma el alumno
+        let mut taken: Vec<(i16, BTreeSet<Slot>)> = Vec::new();
+        for ((subject_id, session_group), by_subgroup) in &sessions {
+            if *session_group != group_id || offered.contains(subject_id) {
+                continue;
+            }
+            if let Some(slots) = by_subgroup.get(&None) {
+                taken.push((*subject_id, slots.clone()));
+            }
+        }
+        for subject_id in chosen {
+            let own = sessions.contains_key(&(*subject_id, group_id));
+            let options: Vec<BTreeSet<Slot>> = sessions
+                .range((*subject_id, i16::MIN)..=(*subject_id, i16::MAX))
+                .filter(|((_, session_group), _)| !own || *session_group == group_id)
+                .flat_map(|(_, by_subgroup)| session_options(by_subgroup))
+                .collect();
+            if let Some(fixed) = options
+                .into_iter()
+                .reduce(|a, b| a.intersection(&b).cloned().collect())
+            {
+                taken.push((*subject_id, fixed));
+            }
+        }
+
+        // Materias distintas en el mismo modulo y en semanas que se enciman
+        let mut by_module: BTreeMap<(String, i16), Vec<(i16, String)>> = BTreeMap::new();
+        for (subject_id, slots) in taken {
+            for (day, module_index, week) in slots {
+                by_module
+                    .entry((day, module_index))
+                    .or_default()
+                    .push((subject_id, week));
+            }
+        }
+        for ((day, module_index), classes) in by_module {
+            let subject_ids: BTreeSet<i16> = classes
+                .iter()
+                .filter(|(subject, week)| {
+                    classes.iter().any(|(other, other_week)| {
+                        other != subject && weeks_overlap(week, other_week)
+                    })
+                })
+                .map(|(subject, _)| *subject)
+                .collect();
+
+            if subject_ids.len() > 1 {
+                collisions.push(StudentCollision {
+                    student_id,
+                    day,
+                    module_index,
+                    subject_ids: subject_ids.into_iter().collect(),
+                });
+            }
+        }
+    }
+
+    collisions
+}
+
+/// Modulo de una clase (dia, modulo, semana)
+type Slot = (String, i16, String);
+
+/// Opciones para tomar una materia en un grupo: las clases de todo el grupo mas las de
+/// uno de los subgrupos (o solo las de todo el grupo si no se divide)
+fn session_options(by_subgroup: &BTreeMap<Option<i16>, BTreeSet<Slot>>) -> Vec<BTreeSet<Slot>> {
+    let common = by_subgroup.get(&None).cloned().unwrap_or_default();
+    let options: Vec<BTreeSet<Slot>> = by_subgroup
+        .iter()
+        .filter(|(subgroup_id, _)| subgroup_id.is_some())
+        .map(|(_, slots)| common.union(slots).cloned().collect())
+        .collect();
+
+    if options.is_empty() {
+        vec![common]
+    } else {
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(
+        group_id: i16,
+        day: &str,
+        module_index: i16,
+        subject_id: i16,
+        subgroup_id: Option<i16>,
+        week: &str,
+    ) -> SessionRow {
+        (
+            group_id,
+            day.to_string(),
+            module_index,
+            subject_id,
+            subgroup_id,
+            week.to_string(),
+        )
+    }
+
+    fn slot(day: &str, module_index: i16) -> Slot {
+        (day.to_string(), module_index, "all".to_string())
+    }
+
+    #[test]
+    fn session_options_add_the_whole_group_to_each_subgroup() {
+        let mut by_subgroup: BTreeMap<Option<i16>, BTreeSet<Slot>> = BTreeMap::new();
+        by_subgroup.insert(None, BTreeSet::from([slot("Lunes", 0)]));
+        by_subgroup.insert(Some(1), BTreeSet::from([slot("Martes", 0)]));
+        by_subgroup.insert(Some(2), BTreeSet::from([slot("Martes", 1)]));
+
+        let options = session_options(&by_subgroup);
+        assert_eq!(
+            options,
+            vec![
+                BTreeSet::from([slot("Lunes", 0), slot("Martes", 0)]),
+                BTreeSet::from([slot("Lunes", 0), slot("Martes", 1)]),
+            ]
+        );
+
+        // Sin subgrupos la unica opcion es todo el grupo
+        by_subgroup.retain(|subgroup_id, _| subgroup_id.is_none());
+        assert_eq!(
+            session_options(&by_subgroup),
+            vec![BTreeSet::from([slot("Lunes", 0)])]
+        );
+    }
+
+    #[test]
+    fn mandatory_subjects_collide_only_in_overlapping_weeks() {
+        let assignments = vec![
+            session(1, "Lunes", 0, 10, None, "all"),
+            session(1, "Lunes", 0, 11, None, "all"),
+            session(1, "Martes", 0, 10, None, "a"),
+            session(1, "Martes", 0, 11, None, "b"),
+            // Las clases de un subgrupo no cuentan
+            session(1, "Miercoles", 0, 10, None, "all"),
+            session(1, "Miercoles", 0, 11, Some(3), "all"),
+        ];
+
+        let collisions = student_collisions(vec![(7, 1)], vec![], assignments, vec![]);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].student_id, 7);
+        assert_eq!(collisions[0].day, "Lunes");
+        assert_eq!(collisions[0].module_index, 0);
+        assert_eq!(collisions[0].subject_ids, vec![10, 11]);
+    }
+
+    #[test]
+    fn group_electives_count_only_when_chosen() {
+        let assignments = vec![
+            session(1, "Lunes", 0, 10, None, "all"),
+            session(1, "Lunes", 0, 20, None, "all"),
+        ];
+        let students = vec![(7, 1), (8, 1)];
+
+        let collisions = student_collisions(students, vec![(8, 20)], assignments, vec![(1, 20)]);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].student_id, 8);
+        assert_eq!(collisions[0].subject_ids, vec![10, 20]);
+    }
+
+    #[test]
+    fn electives_from_other_groups_count_only_common_modules() {
+        // El grupo 1 no da la materia 20; los grupos 2 y 3 la dan en modulos distintos
+        let assignments = vec![
+            session(1, "Lunes", 0, 10, None, "all"),
+            session(1, "Lunes", 1, 10, None, "all"),
+            session(2, "Lunes", 0, 20, None, "all"),
+            session(2, "Lunes", 1, 20, None, "all"),
+            session(3, "Lunes", 1, 20, Some(4), "all"),
+            session(3, "Lunes", 2, 20, Some(5), "all"),
+            session(3, "Lunes", 1, 20, Some(5), "all"),
+        ];
+
+        let collisions = student_collisions(vec![(7, 1)], vec![(7, 20)], assignments, vec![]);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].module_index, 1);
+        assert_eq!(collisions[0].subject_ids, vec![10, 20]);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::class::teachers::SimpleTeacher;
 use crate::db::AppState;
+use crate::util::assignments::delete_empty_combined;
 use futures::TryStreamExt; // Para poder usar try_next() en los streams
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
@@ -120,18 +121,39 @@ pub async fn get_subjects(pool: tauri::State<'_, AppState>) -> Result<Vec<Subjec
 #[allow(dead_code, unused)]
 #[tauri::command]
 pub async fn delete_subject(pool: tauri::State<'_, AppState>, id: i16) -> Result<(), String> {
-    sqlx::query("DELETE FROM subjects WHERE id = ?1")
+    // groups_subjects y teacher_subjects no tienen ON DELETE CASCADE en subject_id, el resto
+    // de las tablas que dependen de la materia se limpian solas
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    sqlx::query("DELETE FROM groups_subjects WHERE subject_id = ?1")
         .bind(id)
-        .execute(&pool.db)
+        .execute(&mut tx)
         .await
         .map_err(|e| format!("Failed to delete subject: {}", e))?;
 
-    sqlx::query("DELETE FROM groups_subjects WHERE subject_id = ?1")
+    sqlx::query("DELETE FROM teacher_subjects WHERE subject_id = ?1")
+        .bind(id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to delete subject: {}", e))?;
+
+    sqlx::query("DELETE FROM subjects WHERE id = ?1")
         .bind(id)
-        .execute(&pool.db)
+        .execute(&mut tx)
         .await
         .map_err(|e| format!("Failed to delete subject: {}", e))?;
 
+    // Las clases compartidas de la materia se quedan sin asignaciones
+    delete_empty_combined(&mut tx).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
     Ok(())
 }
 
@@ -253,3 +275,113 @@ pub async fn get_subjects_with_teachers(
 
     Ok(subjects_with_teachers)
 }
+
+/// Funcion para obtener los tipos de salon que requiere una materia
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `subject_id` - ID de la materia
+/// Retorna un vector con los tipos de salon (ej: Laboratorio, Gimnasio), vacio si puede ir en cualquiera
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_subject_room_types(
+    pool: tauri::State<'_, AppState>,
+    subject_id: i16,
+) -> Result<Vec<String>, String> {
+    let room_types: Vec<String> =
+        sqlx::query_scalar("SELECT room_type FROM subject_room_types WHERE subject_id = ?1")
+            .bind(subject_id)
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Failed to get subject room types: {}", e))?;
+
+    Ok(room_types)
+}
+
+/// Funcion para definir los tipos de salon que requiere una materia
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `subject_id` - ID de la materia
+/// * `room_types` - Tipos de salon compatibles, se compara contra `building_type` del salon
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_subject_room_types(
+    pool: tauri::State<'_, AppState>,
+    subject_id: i16,
+    room_types: Vec<String>,
+) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    // Eliminar los tipos que tenia antes la materia
+    sqlx::query("DELETE FROM subject_room_types WHERE subject_id = ?1")
+        .bind(subject_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to delete subject room types: {}", e))?;
+
+    for room_type in room_types {
+        let room_type = room_type.trim();
+        if room_type.is_empty() {
+            continue;
+        }
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO subject_room_types (subject_id, room_type) VALUES (?1, ?2)",
+        )
+        .bind(subject_id)
+        .bind(room_type)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to attach room type to subject: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para obtener las materias cuyos requisitos de salon no cumple ningun salon registrado
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Retorna un vector con cada materia y los tipos de salon que requiere
+/// Se llama desde la interfaz de usuario para avisar antes de armar el horario
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_unmet_room_requirements(
+    pool: tauri::State<'_, AppState>,
+) -> Result<Vec<(Subject, Vec<String>)>, String> {
+    let subjects: Vec<Subject> = sqlx::query_as::<_, Subject>(
+        "
+        SELECT s.* FROM subjects s
+        WHERE EXISTS (SELECT 1 FROM subject_room_types r WHERE r.subject_id = s.id)
+        AND NOT EXISTS (
+            SELECT 1 FROM subject_room_types r
+            JOIN classroom c ON LOWER(TRIM(c.building_type)) = LOWER(TRIM(r.room_type))
+            WHERE r.subject_id = s.id
+        )
+        ",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get subjects with unmet room requirements: {}", e))?;
+
+    let mut unmet: Vec<(Subject, Vec<String>)> = Vec::new();
+    for subject in subjects {
+        let room_types: Vec<String> =
+            sqlx::query_scalar("SELECT room_type FROM subject_room_types WHERE subject_id = ?1")
+                .bind(subject.id)
+                .fetch_all(&pool.db)
+                .await
+                .map_err(|e| format!("Failed to get subject room types: {}", e))?;
+
+        unmet.push((subject, room_types));
+    }
+
+    Ok(unmet)
+}
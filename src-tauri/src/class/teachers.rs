@@ -1,8 +1,13 @@
 use crate::db::AppState;
+use crate::util::assignments::delete_empty_combined;
 use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use sqlx::error::Error as SqlxError;
-use sqlx::{sqlite::SqliteRow, FromRow, Row};
+use sqlx::{sqlite::SqliteRow, Executor, FromRow, Row, Sqlite};
+
+/// Expresion SQL que cuenta los modulos de un profesor sobre la tabla `assignments`
+/// Las filas de una misma clase compartida (`combined_id`) se cuentan como una sola
+pub const TEACHER_LOAD: &str = "COUNT(DISTINCT COALESCE(-combined_id, id))";
 
 /// Estructura simple de un profesor, solo contiene el ID, el nombre y el primer apellido
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,6 +35,18 @@ pub struct Teacher {
     pub preferred_modules: Vec<i16>,    // Modulos preferidos del profesor
 }
 
+/// Estructura de un bloque en el que el profesor no puede trabajar
+/// A diferencia de `preferred_days` y `preferred_modules` es una restriccion obligatoria
+/// (ej: otro trabajo, otro plantel)
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TeacherUnavailability {
+    pub id: Option<i16>,
+    pub teacher_id: i16,
+    pub day: String,
+    pub module_index: i16,
+    pub reason: Option<String>,
+}
+
 // Implement FromRow for Teacher
 impl<'r> FromRow<'r, SqliteRow> for Teacher {
     fn from_row(row: &'r SqliteRow) -> Result<Self, SqlxError> {
@@ -329,18 +346,32 @@ pub async fn delete_teacher(
     pool: tauri::State<'_, AppState>,
     teacher_id: i16,
 ) -> Result<(), String> {
-    sqlx::query("DELETE FROM teachers WHERE id = ?1")
+    // Las tablas que dependen del profesor se limpian con ON DELETE CASCADE / SET NULL
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    sqlx::query("DELETE FROM assignments WHERE teacher_id = ?1")
         .bind(teacher_id)
-        .execute(&pool.db)
+        .execute(&mut tx)
         .await
         .map_err(|e| format!("Failed to delete teacher: {}", e))?;
 
-    sqlx::query("DELETE FROM assignments WHERE teacher_id = ?1")
+    sqlx::query("DELETE FROM teachers WHERE id = ?1")
         .bind(teacher_id)
-        .execute(&pool.db)
+        .execute(&mut tx)
         .await
         .map_err(|e| format!("Failed to delete teacher: {}", e))?;
 
+    // Las clases compartidas del profesor se quedan sin asignaciones
+    delete_empty_combined(&mut tx).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
     Ok(())
 }
 
@@ -361,3 +392,132 @@ pub async fn delete_teachers(
     }
     Ok(())
 }
+
+/// Funcion para obtener los bloques en los que un profesor no puede trabajar
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `teacher_id` - ID del profesor
+/// Retorna un vector con los bloques no disponibles del profesor
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_teacher_unavailability(
+    pool: tauri::State<'_, AppState>,
+    teacher_id: i16,
+) -> Result<Vec<TeacherUnavailability>, String> {
+    let blocks: Vec<TeacherUnavailability> = sqlx::query_as::<_, TeacherUnavailability>(
+        "SELECT * FROM teacher_unavailability WHERE teacher_id = ?1 ORDER BY day, module_index",
+    )
+    .bind(teacher_id)
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get teacher unavailability: {}", e))?;
+
+    Ok(blocks)
+}
+
+/// Funcion para agregar un bloque en el que el profesor no puede trabajar
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `teacher_id` - ID del profesor
+/// * `day` - Dia del bloque
+/// * `module_index` - Modulo del bloque
+/// * `reason` - Motivo (opcional, ej: otro plantel)
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn add_teacher_unavailability(
+    pool: tauri::State<'_, AppState>,
+    teacher_id: i16,
+    day: String,
+    module_index: i16,
+    reason: Option<String>,
+) -> Result<(), String> {
+    sqlx::query(
+        "
+        INSERT INTO teacher_unavailability (teacher_id, day, module_index, reason)
+        VALUES (?1, ?2, ?3, ?4)
+        ON CONFLICT (teacher_id, day, module_index) DO UPDATE
+        SET reason = excluded.reason
+        ",
+    )
+    .bind(teacher_id)
+    .bind(day)
+    .bind(module_index)
+    .bind(reason)
+    .execute(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to add teacher unavailability: {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para eliminar un bloque no disponible de un profesor
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID del bloque
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_teacher_unavailability(
+    pool: tauri::State<'_, AppState>,
+    id: i16,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM teacher_unavailability WHERE id = ?1")
+        .bind(id)
+        .execute(&pool.db)
+        .await
+        .map_err(|e| format!("Failed to delete teacher unavailability: {}", e))?;
+
+    Ok(())
+}
+
+/// Checa si el profesor tiene marcado el dia y modulo como no disponible
+/// Se utiliza antes de guardar una asignacion, acepta el pool o una transaccion abierta
+pub async fn is_teacher_unavailable<'e, E>(
+    executor: E,
+    teacher_id: i16,
+    day: &str,
+    module_index: i16,
+) -> Result<bool, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let blocked: Option<i16> = sqlx::query_scalar(
+        "
+        SELECT 1 FROM teacher_unavailability
+        WHERE teacher_id = ?1 AND day = ?2 AND module_index = ?3
+        ",
+    )
+    .bind(teacher_id)
+    .bind(day)
+    .bind(module_index)
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| format!("Failed to check teacher unavailability: {}", e))?;
+
+    Ok(blocked.is_some())
+}
+
+/// Funcion para obtener la carga de cada profesor en el horario
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Retorna un vector con el ID del profesor y la cantidad de modulos asignados.
+/// Una clase compartida por varios grupos cuenta una sola vez
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_teacher_loads(
+    pool: tauri::State<'_, AppState>,
+) -> Result<Vec<(i16, i16)>, String> {
+    let loads: Vec<(i16, i16)> = sqlx::query_as(&format!(
+        "
+        SELECT t.id, (SELECT {} FROM assignments a WHERE a.teacher_id = t.id)
+        FROM teachers t
+        ",
+        TEACHER_LOAD
+    ))
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get teacher loads: {}", e))?;
+
+    Ok(loads)
+}
@@ -21,6 +21,9 @@ async fn main() {
             crate::class::subjects::update_subject,
             crate::class::subjects::get_subjects,
             crate::class::subjects::get_subjects_with_teachers,
+            crate::class::subjects::get_subject_room_types,
+            crate::class::subjects::set_subject_room_types,
+            crate::class::subjects::get_unmet_room_requirements,
             // Teachers
             crate::class::teachers::add_teacher,
             crate::class::teachers::create_teachers,
@@ -28,6 +31,15 @@ async fn main() {
             crate::class::teachers::get_all_teachers,
             crate::class::teachers::delete_teacher,
             crate::class::teachers::delete_teachers,
+            crate::class::teachers::get_teacher_unavailability,
+            crate::class::teachers::add_teacher_unavailability,
+            crate::class::teachers::delete_teacher_unavailability,
+            crate::class::teachers::get_teacher_loads,
+            // Absences
+            crate::class::absences::add_teacher_absence,
+            crate::class::absences::get_teacher_absences,
+            crate::class::absences::delete_teacher_absence,
+            crate::class::absences::get_absence_substitutes,
             // Groups
             crate::class::groups::create_group,
             crate::class::groups::create_groups,
@@ -35,6 +47,43 @@ async fn main() {
             crate::class::groups::delete_group,
             crate::class::groups::delete_groups,
             crate::class::groups::get_groups,
+            crate::class::groups::create_subgroup,
+            crate::class::groups::get_subgroups,
+            crate::class::groups::update_subgroup,
+            crate::class::groups::delete_subgroup,
+            // Curriculum
+            crate::class::curriculum::save_curriculum_template,
+            crate::class::curriculum::get_curriculum_templates,
+            crate::class::curriculum::delete_curriculum_template,
+            crate::class::curriculum::preview_curriculum_template,
+            crate::class::curriculum::apply_curriculum_template,
+            // Course loads
+            crate::class::course_loads::get_course_loads,
+            crate::class::course_loads::save_course_load,
+            crate::class::course_loads::delete_course_load,
+            crate::class::course_loads::get_course_load_deviations,
+            crate::class::course_loads::plan_course_loads,
+            crate::class::course_loads::apply_course_load_plan,
+            // School calendar
+            crate::class::school_calendar::get_school_calendar,
+            crate::class::school_calendar::save_school_calendar,
+            // Exams
+            crate::class::exams::plan_exams,
+            crate::class::exams::save_exams,
+            crate::class::exams::get_exams,
+            crate::class::exams::delete_exam,
+            // Students
+            crate::class::students::create_student,
+            crate::class::students::create_students,
+            crate::class::students::get_students,
+            crate::class::students::update_student,
+            crate::class::students::delete_student,
+            crate::class::students::delete_students,
+            crate::class::students::enroll_students,
+            crate::class::students::get_student_electives,
+            crate::class::students::set_student_electives,
+            crate::class::students::set_group_elective,
+            crate::class::students::get_elective_collisions,
             // Classrooms
             crate::class::classrooms::get_classrooms,
             crate::class::classrooms::create_classroom,
@@ -42,12 +91,45 @@ async fn main() {
             crate::class::classrooms::delete_classroom,
             crate::class::classrooms::delete_classrooms,
             crate::class::classrooms::update_classroom,
+            crate::class::classrooms::get_classroom_availability,
+            crate::class::classrooms::set_classroom_availability,
+            crate::class::classrooms::add_classroom_unavailable_block,
+            crate::class::classrooms::remove_classroom_unavailable_block,
+            crate::class::classrooms::get_compatible_classrooms,
             // Utils
             crate::util::xlsx::read_xlsx,
             crate::util::assignments::get_all_assignments,
             crate::util::assignments::get_assignment,
             crate::util::assignments::save_assignment,
             crate::util::assignments::delete_assignment,
+            crate::util::assignments::save_combined_assignment,
+            crate::util::assignments::delete_combined_assignment,
+            crate::util::assignments::move_assignment,
+            crate::util::assignments::swap_assignments,
+            crate::util::assignments::clear_group_assignments,
+            crate::util::assignments::copy_group_assignments,
+            crate::util::assignments::copy_day_assignments,
+            crate::util::conflicts::get_conflicts,
+            crate::util::backup::export_database,
+            crate::util::backup::import_database,
+            crate::util::fet::export_fet,
+            crate::util::fet::import_fet,
+            crate::util::ical::export_ical,
+            crate::util::ical::export_teachers_ical,
+            crate::util::ical::import_teacher_ics,
+            crate::util::mail::email_teacher_timetables,
+            crate::util::search::search,
+            crate::util::server::start_api_server,
+            crate::util::server::stop_api_server,
+            crate::util::server::get_api_server_status,
+            crate::util::site::export_html_site,
+            crate::util::slots::explain_slot,
+            crate::util::slots::suggest_slots,
+            crate::util::snapshots::create_snapshot,
+            crate::util::snapshots::get_snapshots,
+            crate::util::snapshots::delete_snapshot,
+            crate::util::snapshots::restore_snapshot,
+            crate::util::snapshots::diff_snapshots,
         ])
         .plugin(tauri_plugin_store::Builder::default().build())
         .build(tauri::generate_context!())
@@ -70,5 +152,6 @@ async fn main() {
     };
 
     app.manage(AppState { db: pool });
+    app.manage(crate::util::server::ApiServerState::default());
     app.run(|_, _| {});
 }
@@ -1,7 +1,13 @@
-use crate::db::AppState;
+use crate::class::classrooms::{is_classroom_available, is_classroom_compatible};
+use crate::class::school_calendar::is_calendar_slot;
+use crate::class::teachers::is_teacher_unavailable;
+use crate::db::{AppState, DbPool};
+use crate::util::calendar::{parse_week, weeks_overlap, SCHOOL_DAYS};
+use crate::util::conflicts::find_conflicts_in;
 use futures::TryStreamExt; // Para poder usar try_next() en los streams
 use serde::{Deserialize, Serialize};
-use sqlx::{prelude::FromRow, Row};
+use sqlx::{prelude::FromRow, Row, SqliteConnection};
+use std::collections::HashMap;
 
 /// Estructura de la asignacion
 /// Se utiliza para mapear los datos que van y vienen de la base de datos
@@ -13,13 +19,50 @@ pub struct Assignment {
     pub module_index: i16,
     pub subject_id: i16,
     pub teacher_id: i16,
-    pub classroom_id: i16,
+    pub classroom_id: Option<i16>,
+    pub subgroup_id: Option<i16>,
+    pub combined_id: Option<i16>,
+    pub week: String,
     pub subject_shorten: String,
     pub subject_color: String,
 }
 
+/// Cambio de profesor al copiar el horario de un grupo a otro
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeacherSubstitution {
+    pub from_teacher_id: i16,
+    pub to_teacher_id: i16,
+}
+
+/// Datos de una asignacion que se va a copiar
+#[derive(Debug, FromRow)]
+struct AssignmentCopy {
+    group_id: i16,
+    day: String,
+    module_index: i16,
+    subject_id: i16,
+    teacher_id: i16,
+    classroom_id: Option<i16>,
+    subgroup_id: Option<i16>,
+    combined_id: Option<i16>,
+    week: String,
+}
+
+/// Resultado de guardar una asignacion
+/// Si el profesor no es el del plan de carga del grupo se regresa el profesor planeado
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedAssignment {
+    pub teacher_id: i32,
+    pub planned_teacher_id: Option<i32>,
+}
+
 /// Funcion que asigna una materia al modulo
-#[allow(dead_code, unused)]
+/// Si no se manda `teacher_id` se usa el profesor del plan de carga del grupo
+/// Si se manda `subgroup_id` la asignacion es solo para ese subgrupo y puede convivir
+/// con las de los demas subgrupos en el mismo modulo (ej: laboratorios por mitades)
+/// `week` es el patron de semanas (`all`, `a` o `b`), una clase de la semana A y otra de
+/// la semana B pueden compartir el modulo
+#[allow(dead_code, unused, clippy::too_many_arguments)]
 // #[tauri::command]
 #[tauri::command(rename_all = "snake_case")]
 pub async fn save_assignment(
@@ -28,15 +71,120 @@ pub async fn save_assignment(
     day: &str,
     module_index: i32,
     subject_id: i32,
-    teacher_id: i32,
-) -> Result<(), String> {
+    teacher_id: Option<i32>,
+    classroom_id: Option<i32>,
+    subgroup_id: Option<i32>,
+    week: Option<String>,
+) -> Result<SavedAssignment, String> {
+    let week = parse_week(week.as_deref())?;
+    let planned_teacher_id = planned_teacher(&pool.db, group_id, subject_id).await?;
+    let teacher_id = teacher_id.or(planned_teacher_id).ok_or_else(|| {
+        format!(
+            "No teacher given and subject {} has no planned teacher for group {}",
+            subject_id, group_id
+        )
+    })?;
     println!("{}", teacher_id);
-    sqlx::query(
+
+    // Las revisiones y el guardado van en una transaccion para que nadie ocupe el modulo a
+    // la mitad
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    // Un modulo es para todo el grupo o se divide entre subgrupos, no ambos
+    if let Some(subgroup_id) = subgroup_id {
+        let belongs: Option<i16> =
+            sqlx::query_scalar("SELECT 1 FROM subgroups WHERE id = ?1 AND group_id = ?2")
+                .bind(subgroup_id)
+                .bind(group_id)
+                .fetch_optional(&mut tx)
+                .await
+                .map_err(|e| format!("Error checking the subgroup: {}", e))?;
+        if belongs.is_none() {
+            return Err(format!(
+                "Subgroup {} does not belong to group {}",
+                subgroup_id, group_id
+            ));
+        }
+    }
+
+    // Asignaciones del modulo que coinciden en alguna semana: (subgrupo, semana, clase compartida)
+    let taken: Vec<(Option<i32>, String, Option<i16>)> =
+        sqlx::query_as::<_, (Option<i32>, String, Option<i16>)>(
+            "
+            SELECT subgroup_id, week, combined_id FROM assignments
+            WHERE group_id = ?1 AND day = ?2 AND module_index = ?3
+            ",
+        )
+        .bind(group_id)
+        .bind(day)
+        .bind(module_index)
+        .fetch_all(&mut tx)
+        .await
+        .map_err(|e| format!("Error checking the module: {}", e))?
+        .into_iter()
+        .filter(|(_, w, _)| weeks_overlap(w, week))
+        .collect();
+
+    // Una clase compartida se cambia en todos sus grupos a la vez, no desde un solo grupo
+    if let Some(combined_id) = taken.iter().find_map(|(_, _, c)| *c) {
+        return Err(format!(
+            "Module {} on {} is part of combined class {}, change it with \
+             save_combined_assignment or delete_combined_assignment",
+            module_index, day, combined_id
+        ));
+    }
+
+    // Solo se reemplaza la asignacion del mismo lugar y el mismo patron de semanas
+    if let Some((_, w, _)) = taken
+        .iter()
+        .find(|(s, w, _)| *s == subgroup_id && w.as_str() != week)
+    {
+        return Err(format!(
+            "Module {} on {} already has a class in week '{}'",
+            module_index, day, w
+        ));
+    }
+
+    let split = taken.first().map(|(s, _, _)| s.is_some());
+    match (split, subgroup_id) {
+        (Some(false), Some(_)) => {
+            return Err(format!(
+                "Module {} on {} is already assigned to the whole group",
+                module_index, day
+            ))
+        }
+        (Some(true), None) => {
+            return Err(format!(
+                "Module {} on {} is split between subgroups",
+                module_index, day
+            ))
+        }
+        _ => {}
+    }
+
+    check_teacher_and_classroom(
+        &mut tx,
+        day,
+        module_index,
+        subject_id,
+        teacher_id,
+        classroom_id,
+        week,
+        Some((group_id, subgroup_id)),
+    )
+    .await?;
+
+    // Si ya hay una asignacion en el modulo (del grupo o del subgrupo) se reemplaza
+    let updated = sqlx::query(
         "
-        INSERT INTO assignments (group_id, day, module_index, subject_id, teacher_id)
-        VALUES (?1, ?2, ?3, ?4, ?5)
-        ON CONFLICT (group_id, day, module_index) DO UPDATE
-        SET subject_id = excluded.subject_id, teacher_id = excluded.teacher_id
+        UPDATE assignments
+        SET subject_id = ?4, teacher_id = ?5, classroom_id = ?6
+        WHERE group_id = ?1 AND day = ?2 AND module_index = ?3 AND subgroup_id IS ?7
+        AND week = ?8
         ",
     )
     .bind(group_id)
@@ -44,15 +192,50 @@ pub async fn save_assignment(
     .bind(module_index)
     .bind(subject_id)
     .bind(teacher_id)
-    .execute(&pool.db)
+    .bind(classroom_id)
+    .bind(subgroup_id)
+    .bind(week)
+    .execute(&mut tx)
     .await
-    .map_err(|e| format!("Error creating the assignment: {}", e))?;
+    .map_err(|e| format!("Error updating the assignment: {}", e))?;
 
-    Ok(())
+    if updated.rows_affected() == 0 {
+        sqlx::query(
+            "
+            INSERT INTO assignments
+                (group_id, day, module_index, subject_id, teacher_id, classroom_id, subgroup_id,
+                 week)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ",
+        )
+        .bind(group_id)
+        .bind(day)
+        .bind(module_index)
+        .bind(subject_id)
+        .bind(teacher_id)
+        .bind(classroom_id)
+        .bind(subgroup_id)
+        .bind(week)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Error creating the assignment: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    // Avisar si el profesor no es el que se planeo para el grupo
+    Ok(SavedAssignment {
+        teacher_id,
+        planned_teacher_id: planned_teacher_id.filter(|p| *p != teacher_id),
+    })
 }
 
 /// Funcion para obtener una materia al modulo
 /// Retorna la asignacion y el
+/// Si no se manda `subgroup_id` se busca la asignacion de todo el grupo
+/// Si no se manda `week` se busca la asignacion de todas las semanas
 #[allow(dead_code, unused)]
 #[tauri::command]
 pub async fn get_assignment(
@@ -60,17 +243,23 @@ pub async fn get_assignment(
     group_id: i32,
     day: &str,
     module_index: i32,
+    subgroup_id: Option<i32>,
+    week: Option<String>,
 ) -> Result<Option<(i32, i32)>, String> {
+    let week = parse_week(week.as_deref())?;
     let assignment = sqlx::query(
         "
         SELECT subject_id, teacher_id
         FROM assignments
-        WHERE group_id = ?1 AND day = ?2 AND module_index = ?3
+        WHERE group_id = ?1 AND day = ?2 AND module_index = ?3 AND subgroup_id IS ?4
+        AND week = ?5
         ",
     )
     .bind(group_id)
     .bind(day)
     .bind(module_index)
+    .bind(subgroup_id)
+    .bind(week)
     .fetch_optional(&pool.db)
     .await
     .map_err(|e| format!("Error getting assignments {}", e))?;
@@ -92,7 +281,8 @@ pub async fn get_all_assignments(
 ) -> Result<Vec<Assignment>, String> {
     let result: Vec<Assignment> = sqlx::query_as::<_, Assignment>(
         r#"
-        SELECT a.id, a.group_id, a.day, a.module_index, a.teacher_id, 
+        SELECT a.id, a.group_id, a.day, a.module_index, a.teacher_id, a.classroom_id, a.subgroup_id,
+               a.combined_id, a.week,
                s.id as subject_id, s.name as subject_name, s.color as subject_color, s.shorten as subject_shorten
         FROM assignments a
         JOIN subjects s ON a.subject_id = s.id
@@ -113,11 +303,756 @@ pub async fn delete_assignment(
     pool: tauri::State<'_, AppState>,
     assign_id: i32,
 ) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
     sqlx::query("DELETE FROM assignments WHERE id=?")
         .bind(assign_id)
-        .execute(&pool.db)
+        .execute(&mut tx)
         .await
         .map_err(|e| format!("Error deleting assignment: {}", e))?;
 
+    delete_empty_combined(&mut tx).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion que asigna una clase compartida por varios grupos al mismo modulo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_ids` - Grupos que toman la clase juntos
+/// * `name` - Nombre de la clase compartida (opcional, ej: Asamblea)
+/// Todos los grupos deben estar libres en ese modulo, se guarda una fila por grupo
+/// ligadas por `combined_id` para que aparezca en el horario de cada uno
+/// Retorna el ID de la clase compartida si la operacion fue exitosa
+#[allow(dead_code, unused, clippy::too_many_arguments)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn save_combined_assignment(
+    pool: tauri::State<'_, AppState>,
+    group_ids: Vec<i32>,
+    day: &str,
+    module_index: i32,
+    subject_id: i32,
+    teacher_id: i32,
+    classroom_id: Option<i32>,
+    name: Option<String>,
+) -> Result<i16, String> {
+    if group_ids.len() < 2 {
+        return Err("A combined class needs at least two groups".to_string());
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    check_teacher_and_classroom(
+        &mut tx,
+        day,
+        module_index,
+        subject_id,
+        teacher_id,
+        classroom_id,
+        "all",
+        None,
+    )
+    .await?;
+
+    let combined_id: i16 =
+        sqlx::query_scalar("INSERT INTO combined_classes (name) VALUES (?1) RETURNING id")
+            .bind(name)
+            .fetch_one(&mut tx)
+            .await
+            .map_err(|e| format!("Error creating the combined class: {}", e))?;
+
+    for group_id in group_ids {
+        // Todos los grupos deben estar libres en el modulo
+        let busy: Option<i16> = sqlx::query_scalar(
+            "SELECT 1 FROM assignments WHERE group_id = ?1 AND day = ?2 AND module_index = ?3",
+        )
+        .bind(group_id)
+        .bind(day)
+        .bind(module_index)
+        .fetch_optional(&mut tx)
+        .await
+        .map_err(|e| format!("Error checking the module: {}", e))?;
+
+        if busy.is_some() {
+            return Err(format!(
+                "Group {} already has a class on {} at module {}",
+                group_id, day, module_index
+            ));
+        }
+
+        sqlx::query(
+            "
+            INSERT INTO assignments
+                (group_id, day, module_index, subject_id, teacher_id, classroom_id, combined_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ",
+        )
+        .bind(group_id)
+        .bind(day)
+        .bind(module_index)
+        .bind(subject_id)
+        .bind(teacher_id)
+        .bind(classroom_id)
+        .bind(combined_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Error creating the assignment: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(combined_id)
+}
+
+/// Funcion para eliminar una clase compartida de todos sus grupos
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn delete_combined_assignment(
+    pool: tauri::State<'_, AppState>,
+    combined_id: i16,
+) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    sqlx::query("DELETE FROM assignments WHERE combined_id = ?1")
+        .bind(combined_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Error deleting combined assignments: {}", e))?;
+
+    sqlx::query("DELETE FROM combined_classes WHERE id = ?1")
+        .bind(combined_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Error deleting combined class: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para mover una asignacion a otro modulo del mismo grupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `assign_id` - ID de la asignacion
+/// * `day` - Dia destino
+/// * `module_index` - Modulo destino
+/// Si la asignacion es de una clase compartida se mueve en todos sus grupos
+/// El cambio se hace en una transaccion y se cancela si deja conflictos
+/// Retorna las asignaciones movidas
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn move_assignment(
+    pool: tauri::State<'_, AppState>,
+    assign_id: i32,
+    day: &str,
+    module_index: i32,
+) -> Result<Vec<Assignment>, String> {
+    if !is_calendar_slot(&pool.db, day, module_index as i16).await? {
+        return Err(format!(
+            "Module {} on {} is outside the school calendar",
+            module_index, day
+        ));
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let ids = class_ids(&mut tx, assign_id).await?;
+    for id in &ids {
+        sqlx::query("UPDATE assignments SET day = ?2, module_index = ?3 WHERE id = ?1")
+            .bind(id)
+            .bind(day)
+            .bind(module_index)
+            .execute(&mut tx)
+            .await
+            .map_err(|e| format!("Error moving the assignment, the module is taken: {}", e))?;
+    }
+
+    check_no_new_conflicts(&mut tx, &ids).await?;
+    let moved = fetch_assignments(&mut tx, &ids).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(moved)
+}
+
+/// Funcion para intercambiar los modulos de dos asignaciones
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `first_id` - ID de la primera asignacion
+/// * `second_id` - ID de la segunda asignacion
+/// Las asignaciones pueden ser de grupos distintos, cada una toma el dia y modulo de la otra
+/// El cambio se hace en una transaccion y se cancela si deja conflictos
+/// Retorna las asignaciones intercambiadas
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn swap_assignments(
+    pool: tauri::State<'_, AppState>,
+    first_id: i32,
+    second_id: i32,
+) -> Result<Vec<Assignment>, String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let first = class_ids(&mut tx, first_id).await?;
+    let second = class_ids(&mut tx, second_id).await?;
+    if first.iter().any(|id| second.contains(id)) {
+        return Err("Cannot swap an assignment with itself".to_string());
+    }
+
+    let slot = |id: i32| {
+        sqlx::query_as::<_, (String, i16)>(
+            "SELECT day, module_index FROM assignments WHERE id = ?1",
+        )
+        .bind(id)
+    };
+    let (first_day, first_module) = slot(first_id)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|e| format!("Error getting the assignment: {}", e))?;
+    let (second_day, second_module) = slot(second_id)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|e| format!("Error getting the assignment: {}", e))?;
+
+    // SQLite revisa los indices unicos fila por fila, por eso la primera clase
+    // pasa por un modulo temporal antes de tomar su lugar final
+    let moves = [
+        (&first, first_day.as_str(), -1),
+        (&second, first_day.as_str(), first_module),
+        (&first, second_day.as_str(), second_module),
+    ];
+    for (ids, day, module_index) in moves {
+        for id in ids {
+            sqlx::query("UPDATE assignments SET day = ?2, module_index = ?3 WHERE id = ?1")
+                .bind(id)
+                .bind(day)
+                .bind(module_index)
+                .execute(&mut tx)
+                .await
+                .map_err(|e| format!("Error swapping the assignments: {}", e))?;
+        }
+    }
+
+    let ids: Vec<i16> = first.into_iter().chain(second).collect();
+    check_no_new_conflicts(&mut tx, &ids).await?;
+    let swapped = fetch_assignments(&mut tx, &ids).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(swapped)
+}
+
+/// Funcion para borrar el horario de un grupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo
+/// * `day` - Dia a borrar (opcional, si es nulo se borra toda la semana)
+/// Retorna la cantidad de asignaciones borradas
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn clear_group_assignments(
+    pool: tauri::State<'_, AppState>,
+    group_id: i32,
+    day: Option<String>,
+) -> Result<u64, String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let deleted =
+        sqlx::query("DELETE FROM assignments WHERE group_id = ?1 AND (?2 IS NULL OR day = ?2)")
+            .bind(group_id)
+            .bind(day)
+            .execute(&mut tx)
+            .await
+            .map_err(|e| format!("Error clearing the group assignments: {}", e))?;
+
+    delete_empty_combined(&mut tx).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(deleted.rows_affected())
+}
+
+/// Funcion para copiar el horario de un grupo a otro (ej: grupos paralelos)
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `source_group_id` - Grupo del que se copia
+/// * `target_group_id` - Grupo al que se copia, su horario actual se reemplaza
+/// * `substitutions` - Cambios de profesor a aplicar
+/// El profesor de cada clase es el de `substitutions`, si no hay se usa el del plan de carga
+/// del grupo destino y si tampoco hay se deja el mismo. Los subgrupos se ligan por nombre,
+/// el grupo destino se une a las clases compartidas con su mismo profesor y las demas
+/// clases se copian sin salon
+/// El cambio se hace en una transaccion y se cancela si deja conflictos
+/// Retorna las asignaciones creadas
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn copy_group_assignments(
+    pool: tauri::State<'_, AppState>,
+    source_group_id: i32,
+    target_group_id: i32,
+    substitutions: Vec<TeacherSubstitution>,
+) -> Result<Vec<Assignment>, String> {
+    if source_group_id == target_group_id {
+        return Err("The source and target groups are the same".to_string());
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let rows: Vec<AssignmentCopy> = sqlx::query_as::<_, AssignmentCopy>(
+        "SELECT * FROM assignments WHERE group_id = ?1 ORDER BY day, module_index",
+    )
+    .bind(source_group_id)
+    .fetch_all(&mut tx)
+    .await
+    .map_err(|e| format!("Error getting the group assignments: {}", e))?;
+
+    sqlx::query("DELETE FROM assignments WHERE group_id = ?1")
+        .bind(target_group_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Error clearing the target group: {}", e))?;
+    delete_empty_combined(&mut tx).await?;
+
+    let mut ids: Vec<i16> = Vec::new();
+    for row in rows {
+        let planned: Option<i16> = sqlx::query_scalar(
+            "SELECT teacher_id FROM course_loads WHERE group_id = ?1 AND subject_id = ?2",
+        )
+        .bind(target_group_id)
+        .bind(row.subject_id)
+        .fetch_optional(&mut tx)
+        .await
+        .map_err(|e| format!("Error getting the planned teacher: {}", e))?;
+
+        // Una clase compartida tiene un solo profesor para todos sus grupos
+        let teacher_id = match row.combined_id {
+            Some(_) => row.teacher_id,
+            None => substitutions
+                .iter()
+                .find(|s| s.from_teacher_id == row.teacher_id)
+                .map(|s| s.to_teacher_id)
+                .or(planned)
+                .unwrap_or(row.teacher_id),
+        };
+
+        // El subgrupo destino es el que tiene el mismo nombre
+        let subgroup_id: Option<i16> = match row.subgroup_id {
+            Some(subgroup_id) => Some(
+                sqlx::query_scalar(
+                    "
+                    SELECT t.id FROM subgroups t
+                    JOIN subgroups s ON s.name = t.name
+                    WHERE s.id = ?1 AND t.group_id = ?2
+                    ",
+                )
+                .bind(subgroup_id)
+                .bind(target_group_id)
+                .fetch_optional(&mut tx)
+                .await
+                .map_err(|e| format!("Error getting the target subgroup: {}", e))?
+                .ok_or_else(|| {
+                    format!(
+                        "Group {} has no subgroup matching subgroup {}",
+                        target_group_id, subgroup_id
+                    )
+                })?,
+            ),
+            None => None,
+        };
+
+        let classroom_id = row.combined_id.and(row.classroom_id);
+
+        let id: i16 = sqlx::query_scalar(
+            "
+            INSERT INTO assignments
+                (group_id, day, module_index, subject_id, teacher_id, classroom_id, subgroup_id,
+                 combined_id, week)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            RETURNING id
+            ",
+        )
+        .bind(target_group_id)
+        .bind(&row.day)
+        .bind(row.module_index)
+        .bind(row.subject_id)
+        .bind(teacher_id)
+        .bind(classroom_id)
+        .bind(subgroup_id)
+        .bind(row.combined_id)
+        .bind(&row.week)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|e| format!("Error copying the assignment: {}", e))?;
+        ids.push(id);
+    }
+
+    check_no_new_conflicts(&mut tx, &ids).await?;
+    let copied = fetch_assignments(&mut tx, &ids).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(copied)
+}
+
+/// Funcion para duplicar un dia del horario en otro dia
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo (opcional, si es nulo se copia el dia de todos los grupos)
+/// * `source_day` - Dia que se copia
+/// * `target_day` - Dia destino, sus asignaciones actuales se reemplazan
+/// Las clases compartidas se copian como nuevas clases compartidas del dia destino, si solo
+/// se copia uno de sus grupos se copia como una clase normal
+/// El cambio se hace en una transaccion y se cancela si deja conflictos
+/// Retorna las asignaciones creadas
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn copy_day_assignments(
+    pool: tauri::State<'_, AppState>,
+    group_id: Option<i32>,
+    source_day: &str,
+    target_day: &str,
+) -> Result<Vec<Assignment>, String> {
+    if source_day == target_day {
+        return Err("The source and target days are the same".to_string());
+    }
+    if !SCHOOL_DAYS.contains(&target_day) {
+        return Err(format!("{} is outside the school calendar", target_day));
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let rows: Vec<AssignmentCopy> = sqlx::query_as::<_, AssignmentCopy>(
+        "
+        SELECT * FROM assignments
+        WHERE day = ?1 AND (?2 IS NULL OR group_id = ?2)
+        ORDER BY group_id, module_index
+        ",
+    )
+    .bind(source_day)
+    .bind(group_id)
+    .fetch_all(&mut tx)
+    .await
+    .map_err(|e| format!("Error getting the day assignments: {}", e))?;
+
+    sqlx::query("DELETE FROM assignments WHERE day = ?1 AND (?2 IS NULL OR group_id = ?2)")
+        .bind(target_day)
+        .bind(group_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Error clearing the target day: {}", e))?;
+    delete_empty_combined(&mut tx).await?;
+
+    // Grupos de cada clase compartida que se copian, si solo se copia un grupo (ej: al copiar
+    // el dia de un solo grupo) la clase queda como una clase normal de ese grupo
+    let mut copied_groups: HashMap<i16, usize> = HashMap::new();
+    for combined_id in rows.iter().filter_map(|r| r.combined_id) {
+        *copied_groups.entry(combined_id).or_default() += 1;
+    }
+
+    // Cada clase compartida del dia origen se vuelve una nueva en el dia destino
+    let mut combined: HashMap<i16, i16> = HashMap::new();
+    let mut ids: Vec<i16> = Vec::new();
+    for row in rows {
+        let shared = row.combined_id.filter(|id| copied_groups[id] > 1);
+        let combined_id = match shared {
+            Some(old_id) => match combined.get(&old_id) {
+                Some(new_id) => Some(*new_id),
+                None => {
+                    let new_id: i16 = sqlx::query_scalar(
+                        "
+                        INSERT INTO combined_classes (name)
+                        SELECT name FROM combined_classes WHERE id = ?1
+                        RETURNING id
+                        ",
+                    )
+                    .bind(old_id)
+                    .fetch_one(&mut tx)
+                    .await
+                    .map_err(|e| format!("Error copying the combined class: {}", e))?;
+                    combined.insert(old_id, new_id);
+                    Some(new_id)
+                }
+            },
+            None => None,
+        };
+
+        let id: i16 = sqlx::query_scalar(
+            "
+            INSERT INTO assignments
+                (group_id, day, module_index, subject_id, teacher_id, classroom_id, subgroup_id,
+                 combined_id, week)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            RETURNING id
+            ",
+        )
+        .bind(row.group_id)
+        .bind(target_day)
+        .bind(row.module_index)
+        .bind(row.subject_id)
+        .bind(row.teacher_id)
+        .bind(row.classroom_id)
+        .bind(row.subgroup_id)
+        .bind(combined_id)
+        .bind(&row.week)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|e| format!("Error copying the assignment: {}", e))?;
+        ids.push(id);
+    }
+
+    check_no_new_conflicts(&mut tx, &ids).await?;
+    let copied = fetch_assignments(&mut tx, &ids).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(copied)
+}
+
+/// Borra las clases compartidas que se quedaron sin grupos
+pub async fn delete_empty_combined(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        "
+        DELETE FROM combined_classes
+        WHERE id NOT IN (SELECT combined_id FROM assignments WHERE combined_id IS NOT NULL)
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Error deleting empty combined classes: {}", e))?;
+
+    Ok(())
+}
+
+/// Regresa los IDs de las filas de la clase de una asignacion
+/// (todas las filas si es una clase compartida por varios grupos)
+async fn class_ids(conn: &mut SqliteConnection, assign_id: i32) -> Result<Vec<i16>, String> {
+    let ids: Vec<i16> = sqlx::query_scalar(
+        "
+        SELECT id FROM assignments
+        WHERE id = ?1
+        OR combined_id = (SELECT combined_id FROM assignments WHERE id = ?1)
+        ORDER BY id
+        ",
+    )
+    .bind(assign_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Error getting the assignment: {}", e))?;
+
+    if ids.is_empty() {
+        return Err(format!("Assignment {} not found", assign_id));
+    }
+
+    Ok(ids)
+}
+
+/// Revisa que las asignaciones cambiadas no esten en ningun conflicto
+/// (grupo, profesor o salon ocupados, bloques no disponibles)
+async fn check_no_new_conflicts(conn: &mut SqliteConnection, ids: &[i16]) -> Result<(), String> {
+    let conflicts: Vec<String> = find_conflicts_in(conn)
+        .await?
+        .into_iter()
+        .filter(|c| c.assignment_ids.iter().any(|id| ids.contains(id)))
+        .map(|c| format!("{:?} on {} at module {}", c.kind, c.day, c.module_index))
+        .collect();
+
+    if !conflicts.is_empty() {
+        return Err(format!(
+            "The change would leave conflicts: {}",
+            conflicts.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Obtiene las asignaciones con los datos de su materia
+async fn fetch_assignments(
+    conn: &mut SqliteConnection,
+    ids: &[i16],
+) -> Result<Vec<Assignment>, String> {
+    let mut assignments: Vec<Assignment> = Vec::new();
+    for id in ids {
+        let assignment = sqlx::query_as::<_, Assignment>(
+            r#"
+            SELECT a.id, a.group_id, a.day, a.module_index, a.teacher_id, a.classroom_id,
+                   a.subgroup_id, a.combined_id, a.week,
+                   s.id as subject_id, s.color as subject_color, s.shorten as subject_shorten
+            FROM assignments a
+            JOIN subjects s ON a.subject_id = s.id
+            WHERE a.id = ?1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Error getting the assignment: {}", e))?;
+        assignments.push(assignment);
+    }
+
+    Ok(assignments)
+}
+
+/// Obtiene el profesor del plan de carga para la materia en el grupo
+pub async fn planned_teacher(
+    pool: &DbPool,
+    group_id: i32,
+    subject_id: i32,
+) -> Result<Option<i32>, String> {
+    sqlx::query_scalar(
+        "SELECT teacher_id FROM course_loads WHERE group_id = ?1 AND subject_id = ?2",
+    )
+    .bind(group_id)
+    .bind(subject_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Error getting the planned teacher: {}", e))
+}
+
+/// Revisa que el profesor y el salon se puedan usar en el modulo
+/// `replaces` es el lugar (grupo, subgrupo) cuya clase se va a reemplazar, esa clase no
+/// cuenta como ocupada para el profesor ni para el salon
+#[allow(clippy::too_many_arguments)]
+async fn check_teacher_and_classroom(
+    conn: &mut SqliteConnection,
+    day: &str,
+    module_index: i32,
+    subject_id: i32,
+    teacher_id: i32,
+    classroom_id: Option<i32>,
+    week: &str,
+    replaces: Option<(i32, Option<i32>)>,
+) -> Result<(), String> {
+    // El profesor no puede dar dos clases en el mismo modulo y la misma semana
+    let (group_id, subgroup_id) = replaces.unzip();
+    let busy: Option<i16> = sqlx::query_scalar(
+        "
+        SELECT 1 FROM assignments
+        WHERE teacher_id = ?1 AND day = ?2 AND module_index = ?3
+        AND (week = 'all' OR ?4 = 'all' OR week = ?4)
+        AND NOT (group_id IS ?5 AND subgroup_id IS ?6 AND week = ?4)
+        ",
+    )
+    .bind(teacher_id)
+    .bind(day)
+    .bind(module_index)
+    .bind(week)
+    .bind(group_id)
+    .bind(subgroup_id.flatten())
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("Error checking the teacher schedule: {}", e))?;
+
+    if busy.is_some() {
+        return Err(format!(
+            "Teacher {} already has a class on {} at module {}",
+            teacher_id, day, module_index
+        ));
+    }
+
+    // El profesor no puede trabajar en los bloques que tiene marcados como no disponibles
+    if is_teacher_unavailable(&mut *conn, teacher_id as i16, day, module_index as i16).await? {
+        return Err(format!(
+            "Teacher {} is not available on {} at module {}",
+            teacher_id, day, module_index
+        ));
+    }
+
+    if let Some(classroom_id) = classroom_id {
+        // El salon no puede tener dos clases en el mismo modulo y la misma semana, las filas
+        // de una clase compartida son una sola clase y ya ocupan el salon
+        let busy: Option<i16> = sqlx::query_scalar(
+            "
+            SELECT 1 FROM assignments
+            WHERE classroom_id = ?1 AND day = ?2 AND module_index = ?3
+            AND (week = 'all' OR ?4 = 'all' OR week = ?4)
+            AND NOT (group_id IS ?5 AND subgroup_id IS ?6 AND week = ?4)
+            ",
+        )
+        .bind(classroom_id)
+        .bind(day)
+        .bind(module_index)
+        .bind(week)
+        .bind(group_id)
+        .bind(subgroup_id.flatten())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| format!("Error checking the classroom schedule: {}", e))?;
+
+        if busy.is_some() {
+            return Err(format!(
+                "Classroom {} is already used on {} at module {}",
+                classroom_id, day, module_index
+            ));
+        }
+
+        // El salon no se puede asignar en los bloques que tiene marcados como no disponibles
+        if !is_classroom_available(&mut *conn, classroom_id as i16, day, module_index as i16)
+            .await?
+        {
+            return Err(format!(
+                "Classroom {} is not available on {} at module {}",
+                classroom_id, day, module_index
+            ));
+        }
+
+        // El tipo de salon debe ser el que requiere la materia (ej: laboratorio)
+        if !is_classroom_compatible(&mut *conn, classroom_id as i16, subject_id as i16).await? {
+            return Err(format!(
+                "Classroom {} does not meet the room requirements of subject {}",
+                classroom_id, subject_id
+            ));
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,460 @@
+use crate::db::{AppState, DbPool};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, Row, SqliteConnection, TypeInfo, ValueRef};
+use std::collections::{BTreeMap, HashMap};
+
+/// Identificador del formato del respaldo
+const BACKUP_FORMAT: &str = "school_roster";
+
+/// Version del formato, se sube cuando cambian las tablas o columnas del respaldo
+pub const BACKUP_VERSION: i64 = 1;
+
+/// Tabla del respaldo
+/// `has_id` indica si la tabla tiene un `id` que se vuelve a generar al importar
+/// y `references` son las columnas que apuntan al `id` de otra tabla.
+/// Las columnas de `loose_references` se actualizan si la fila existe, si no se dejan igual
+/// al reemplazar la base de datos o se dejan nulas al agregar los datos a los que ya existen
+/// (ej: copias del horario de grupos que ya se borraron)
+struct BackupTable {
+    name: &'static str,
+    has_id: bool,
+    references: &'static [(&'static str, &'static str)],
+    loose_references: &'static [(&'static str, &'static str)],
+}
+
+/// Tablas del respaldo en el orden en que se importan (primero las que no dependen de otras)
+const TABLES: &[BackupTable] = &[
+    BackupTable {
+        name: "subjects",
+        has_id: true,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "teachers",
+        has_id: true,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "groups",
+        has_id: true,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "classroom",
+        has_id: true,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "teacher_subjects",
+        has_id: false,
+        references: &[("teacher_id", "teachers"), ("subject_id", "subjects")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "groups_subjects",
+        has_id: false,
+        references: &[("group_id", "groups"), ("subject_id", "subjects")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "subject_room_types",
+        has_id: false,
+        references: &[("subject_id", "subjects")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "teacher_unavailability",
+        has_id: true,
+        references: &[("teacher_id", "teachers")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "teacher_absences",
+        has_id: true,
+        references: &[("teacher_id", "teachers")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "subgroups",
+        has_id: true,
+        references: &[("group_id", "groups")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "combined_classes",
+        has_id: true,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "assignments",
+        has_id: true,
+        references: &[
+            ("group_id", "groups"),
+            ("subject_id", "subjects"),
+            ("teacher_id", "teachers"),
+            ("classroom_id", "classroom"),
+            ("subgroup_id", "subgroups"),
+            ("combined_id", "combined_classes"),
+        ],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "students",
+        has_id: true,
+        references: &[("group_id", "groups")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "student_electives",
+        has_id: false,
+        references: &[("student_id", "students"), ("subject_id", "subjects")],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "curriculum_templates",
+        has_id: true,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "curriculum_subjects",
+        has_id: false,
+        references: &[
+            ("template_id", "curriculum_templates"),
+            ("subject_id", "subjects"),
+        ],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "course_loads",
+        has_id: true,
+        references: &[
+            ("group_id", "groups"),
+            ("subject_id", "subjects"),
+            ("teacher_id", "teachers"),
+        ],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "schedule_snapshots",
+        has_id: true,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "snapshot_assignments",
+        has_id: false,
+        references: &[("snapshot_id", "schedule_snapshots")],
+        loose_references: &[
+            ("group_id", "groups"),
+            ("subject_id", "subjects"),
+            ("teacher_id", "teachers"),
+            ("classroom_id", "classroom"),
+            ("subgroup_id", "subgroups"),
+            ("combined_id", "combined_classes"),
+        ],
+    },
+    BackupTable {
+        name: "exams",
+        has_id: true,
+        references: &[
+            ("group_id", "groups"),
+            ("subject_id", "subjects"),
+            ("classroom_id", "classroom"),
+            ("invigilator_id", "teachers"),
+        ],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "school_calendar",
+        has_id: false,
+        references: &[],
+        loose_references: &[],
+    },
+    BackupTable {
+        name: "module_times",
+        has_id: false,
+        references: &[],
+        loose_references: &[],
+    },
+];
+
+/// Documento del respaldo
+/// `tables` tiene las filas de cada tabla como objetos columna -> valor
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub format: String,
+    pub version: i64,
+    pub exported_at: String,
+    pub tables: BTreeMap<String, Vec<Map<String, Value>>>,
+}
+
+/// Resultado de importar un respaldo: filas cargadas por tabla
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupImport {
+    pub tables: BTreeMap<String, i64>,
+}
+
+/// Funcion para exportar toda la base de datos a un archivo JSON
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `path` - Ruta del archivo a crear
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn export_database(pool: tauri::State<'_, AppState>, path: String) -> Result<(), String> {
+    let backup = build_backup(&pool.db).await?;
+    let json = serde_json::to_string_pretty(&backup)
+        .map_err(|e| format!("Failed to serialize backup: {}", e))?;
+
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Funcion para importar un respaldo JSON
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `path` - Ruta del archivo del respaldo
+/// * `replace` - Si es verdadero se borran todos los datos antes de importar, si no los datos
+///   del respaldo se agregan a los que ya existen
+/// Los IDs se vuelven a generar y las referencias entre tablas se actualizan.
+/// Todo se hace en una transaccion, si algo falla no se cambia nada
+/// Retorna la cantidad de filas importadas por tabla
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn import_database(
+    pool: tauri::State<'_, AppState>,
+    path: String,
+    replace: bool,
+) -> Result<BackupImport, String> {
+    let json =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let backup: Backup =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid backup file: {}", e))?;
+
+    load_backup(&pool.db, backup, replace).await
+}
+
+/// Lee todas las tablas del respaldo
+pub async fn build_backup(pool: &DbPool) -> Result<Backup, String> {
+    let mut tables: BTreeMap<String, Vec<Map<String, Value>>> = BTreeMap::new();
+    for table in TABLES {
+        let rows: Vec<SqliteRow> = sqlx::query(&format!("SELECT * FROM {}", table.name))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to read table {}: {}", table.name, e))?;
+
+        let mut values: Vec<Map<String, Value>> = Vec::new();
+        for row in rows {
+            values.push(row_to_json(&row)?);
+        }
+        tables.insert(table.name.to_string(), values);
+    }
+
+    Ok(Backup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        tables,
+    })
+}
+
+/// Valida y carga un respaldo en la base de datos
+pub async fn load_backup(
+    pool: &DbPool,
+    backup: Backup,
+    replace: bool,
+) -> Result<BackupImport, String> {
+    if backup.format != BACKUP_FORMAT {
+        return Err(format!("Unknown backup format '{}'", backup.format));
+    }
+    if backup.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup version {} is newer than the supported version {}",
+            backup.version, BACKUP_VERSION
+        ));
+    }
+    if let Some(name) = backup
+        .tables
+        .keys()
+        .find(|name| !TABLES.iter().any(|t| t.name == name.as_str()))
+    {
+        return Err(format!("Unknown table '{}' in backup", name));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    if replace {
+        for table in TABLES.iter().rev() {
+            sqlx::query(&format!("DELETE FROM {}", table.name))
+                .execute(&mut tx)
+                .await
+                .map_err(|e| format!("Failed to clear table {}: {}", table.name, e))?;
+        }
+    }
+
+    // IDs del respaldo -> IDs nuevos, por tabla
+    let mut ids: HashMap<&str, HashMap<i64, i64>> = HashMap::new();
+    let mut imported: BTreeMap<String, i64> = BTreeMap::new();
+
+    for table in TABLES {
+        let Some(rows) = backup.tables.get(table.name) else {
+            continue;
+        };
+        let columns = table_columns(&mut tx, table.name).await?;
+
+        let mut count = 0;
+        for row in rows {
+            if let Some(column) = row.keys().find(|c| !columns.iter().any(|(n, _)| n == *c)) {
+                return Err(format!(
+                    "Unknown column '{}' in table {}",
+                    column, table.name
+                ));
+            }
+
+            let mut row = row.clone();
+            let old_id = if table.has_id {
+                row.remove("id").and_then(|id| id.as_i64())
+            } else {
+                None
+            };
+
+            for (column, target) in table.references {
+                let Some(old) = row.get(*column).and_then(|v| v.as_i64()) else {
+                    continue;
+                };
+                let new = ids.get(target).and_then(|m| m.get(&old)).ok_or_else(|| {
+                    format!(
+                        "Table {} references {} {} that is not in the backup",
+                        table.name, target, old
+                    )
+                })?;
+                row.insert(column.to_string(), Value::from(*new));
+            }
+
+            for (column, target) in table.loose_references {
+                let Some(old) = row.get(*column).and_then(|v| v.as_i64()) else {
+                    continue;
+                };
+                if let Some(new) = ids.get(target).and_then(|m| m.get(&old)) {
+                    row.insert(column.to_string(), Value::from(*new));
+                    continue;
+                }
+                if replace {
+                    continue;
+                }
+
+                // Al agregar datos el ID viejo puede ser de otra fila de la base de datos
+                let not_null = columns
+                    .iter()
+                    .any(|(n, not_null)| n == *column && *not_null);
+                if not_null {
+                    return Err(format!(
+                        "Table {} references {} {} that is not in the backup, import it replacing the database",
+                        table.name, target, old
+                    ));
+                }
+                row.insert(column.to_string(), Value::Null);
+            }
+
+            // Las tablas de una sola fila o con llave natural se reemplazan
+            let verb = if table.has_id {
+                "INSERT"
+            } else {
+                "INSERT OR REPLACE"
+            };
+            let names: Vec<&String> = row.keys().collect();
+            let sql = format!(
+                "{} INTO {} ({}) VALUES ({}) {}",
+                verb,
+                table.name,
+                names
+                    .iter()
+                    .map(|c| format!("\"{}\"", c))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                (1..=names.len())
+                    .map(|i| format!("?{}", i))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                if table.has_id { "RETURNING id" } else { "" }
+            );
+
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            for value in row.values() {
+                query = match value {
+                    Value::Null => query.bind(None::<String>),
+                    Value::Bool(b) => query.bind(*b),
+                    Value::Number(n) => match n.as_i64() {
+                        Some(i) => query.bind(i),
+                        None => query.bind(n.as_f64()),
+                    },
+                    Value::String(s) => query.bind(s.clone()),
+                    other => query.bind(other.to_string()),
+                };
+            }
+
+            let new_id = query
+                .fetch_optional(&mut tx)
+                .await
+                .map_err(|e| format!("Failed to import into table {}: {}", table.name, e))?;
+            if let (Some(old_id), Some(new_id)) = (old_id, new_id) {
+                ids.entry(table.name).or_default().insert(old_id, new_id);
+            }
+            count += 1;
+        }
+        imported.insert(table.name.to_string(), count);
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(BackupImport { tables: imported })
+}
+
+/// Columnas de una tabla: (nombre, no acepta nulos)
+async fn table_columns(
+    conn: &mut SqliteConnection,
+    table: &str,
+) -> Result<Vec<(String, bool)>, String> {
+    sqlx::query_as("SELECT name, \"notnull\" FROM pragma_table_info(?1)")
+        .bind(table)
+        .fetch_all(conn)
+        .await
+        .map_err(|e| format!("Failed to get columns of table {}: {}", table, e))
+}
+
+/// Convierte una fila a un objeto JSON segun el tipo de cada valor
+fn row_to_json(row: &SqliteRow) -> Result<Map<String, Value>, String> {
+    let mut object = Map::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let raw = row
+            .try_get_raw(i)
+            .map_err(|e| format!("Failed to read column {}: {}", column.name(), e))?;
+
+        let value = if raw.is_null() {
+            Value::Null
+        } else {
+            let type_name = raw.type_info().name().to_string();
+            let read_error = |e: sqlx::Error| format!("Failed to read {}: {}", column.name(), e);
+            match type_name.as_str() {
+                "INTEGER" | "BOOLEAN" => Value::from(row.try_get::<i64, _>(i).map_err(read_error)?),
+                "REAL" => Value::from(row.try_get::<f64, _>(i).map_err(read_error)?),
+                _ => Value::from(row.try_get::<String, _>(i).map_err(read_error)?),
+            }
+        };
+        object.insert(column.name().to_string(), value);
+    }
+
+    Ok(object)
+}
@@ -0,0 +1,110 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+
+/// Nombres de los dias tal como se guardan en las asignaciones
+pub const WEEK_DAYS: [&str; 7] = [
+    "Lunes",
+    "Martes",
+    "Miercoles",
+    "Jueves",
+    "Viernes",
+    "Sabado",
+    "Domingo",
+];
+
+/// Convierte una fecha con formato `YYYY-MM-DD` a `NaiveDate`
+pub fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{}' (expected YYYY-MM-DD): {}", date, e))
+}
+
+/// Convierte una hora con formato `HH:MM` a `NaiveTime`
+pub fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|e| format!("Invalid time '{}' (expected HH:MM): {}", time, e))
+}
+
+/// Regresa el nombre del dia de la semana de una fecha (ej: 2024-09-02 -> "Lunes")
+pub fn day_name(date: NaiveDate) -> &'static str {
+    WEEK_DAYS[date.weekday().num_days_from_monday() as usize]
+}
+
+/// Regresa todas las fechas entre `start` y `end` (incluidas)
+pub fn dates_between(start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    start.iter_days().take_while(|d| *d <= end).collect()
+}
+
+/// Dias en los que hay clases (los que muestra la cuadricula del horario)
+pub const SCHOOL_DAYS: [&str; 5] = ["Lunes", "Martes", "Miercoles", "Jueves", "Viernes"];
+
+/// Cantidad de modulos por dia
+pub const MODULES_PER_DAY: i16 = 9;
+
+/// Checa si el dia y modulo estan dentro del calendario escolar
+pub fn is_school_slot(day: &str, module_index: i16) -> bool {
+    SCHOOL_DAYS.contains(&day) && (0..MODULES_PER_DAY).contains(&module_index)
+}
+
+/// Patrones de semana de una asignacion: todas las semanas, semana A o semana B
+pub const WEEK_PATTERNS: [&str; 3] = ["all", "a", "b"];
+
+/// Valida el patron de semana, si es nulo la clase es todas las semanas
+pub fn parse_week(week: Option<&str>) -> Result<&'static str, String> {
+    let week = week.unwrap_or("all").trim().to_lowercase();
+    WEEK_PATTERNS
+        .iter()
+        .find(|w| **w == week)
+        .copied()
+        .ok_or_else(|| format!("Invalid week pattern '{}' (expected all, a or b)", week))
+}
+
+/// Semana (`a` o `b`) de una fecha, la semana de `week_a` es A y se alternan desde ahi
+pub fn week_of(date: NaiveDate, week_a: NaiveDate) -> &'static str {
+    let monday = |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+    if ((monday(date) - monday(week_a)).num_days() / 7).rem_euclid(2) == 0 {
+        "a"
+    } else {
+        "b"
+    }
+}
+
+/// Checa si dos patrones de semana coinciden en alguna semana
+/// Una clase de la semana A y otra de la semana B no se enciman
+pub fn weeks_overlap(first: &str, second: &str) -> bool {
+    first == "all" || second == "all" || first == second
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_week_defaults_to_every_week() {
+        assert_eq!(parse_week(None), Ok("all"));
+        assert_eq!(parse_week(Some(" A ")), Ok("a"));
+        assert_eq!(parse_week(Some("b")), Ok("b"));
+        assert!(parse_week(Some("c")).is_err());
+        assert!(parse_week(Some("")).is_err());
+    }
+
+    #[test]
+    fn weeks_overlap_only_splits_a_and_b() {
+        assert!(weeks_overlap("all", "all"));
+        assert!(weeks_overlap("all", "a"));
+        assert!(weeks_overlap("b", "all"));
+        assert!(weeks_overlap("a", "a"));
+        assert!(!weeks_overlap("a", "b"));
+        assert!(!weeks_overlap("b", "a"));
+    }
+
+    #[test]
+    fn week_of_alternates_from_the_week_a() {
+        let week_a = parse_date("2024-09-04").unwrap();
+        // Toda la semana del 2 al 8 de septiembre es A, aunque sea antes de la fecha
+        assert_eq!(week_of(parse_date("2024-09-02").unwrap(), week_a), "a");
+        assert_eq!(week_of(parse_date("2024-09-08").unwrap(), week_a), "a");
+        assert_eq!(week_of(parse_date("2024-09-09").unwrap(), week_a), "b");
+        assert_eq!(week_of(parse_date("2024-09-16").unwrap(), week_a), "a");
+        // Tambien funciona con fechas anteriores a la semana A
+        assert_eq!(week_of(parse_date("2024-08-30").unwrap(), week_a), "b");
+    }
+}
@@ -0,0 +1,243 @@
+use crate::class::classrooms::Classroom;
+use crate::db::{AppState, DbPool};
+use crate::util::calendar::weeks_overlap;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::SqliteConnection;
+use std::collections::BTreeMap;
+
+/// Tipo de conflicto encontrado en el horario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// El modulo tiene una clase de todo el grupo y otra de un subgrupo (o dos clases
+    /// del grupo en la misma semana)
+    GroupDoubleBooked,
+    /// El profesor tiene dos grupos en el mismo modulo
+    TeacherDoubleBooked,
+    /// El salon esta ocupado por dos grupos en el mismo modulo
+    ClassroomDoubleBooked,
+    /// El profesor no puede trabajar en ese modulo
+    TeacherUnavailable,
+    /// El salon no se puede usar en ese modulo
+    ClassroomUnavailable,
+}
+
+/// Estructura de un conflicto del horario
+/// Contiene las asignaciones involucradas para poder señalarlas en la interfaz
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub day: String,
+    pub module_index: i16,
+    pub group_id: Option<i16>,
+    pub teacher_id: Option<i16>,
+    pub classroom_id: Option<i16>,
+    pub assignment_ids: Vec<i16>,
+}
+
+/// Datos minimos de una asignacion para revisar conflictos
+#[derive(Debug, FromRow)]
+struct AssignmentRow {
+    id: i16,
+    group_id: i16,
+    day: String,
+    module_index: i16,
+    teacher_id: i16,
+    classroom_id: Option<i16>,
+    subgroup_id: Option<i16>,
+    combined_id: Option<i16>,
+    week: String,
+}
+
+impl AssignmentRow {
+    /// Identifica la clase: las filas de una clase compartida son la misma clase
+    fn class_key(&self) -> i32 {
+        match self.combined_id {
+            Some(combined_id) => -(combined_id as i32),
+            None => self.id as i32,
+        }
+    }
+}
+
+/// Checa si hay dos clases distintas que coinciden en alguna semana
+/// (una clase de la semana A y otra de la semana B no chocan)
+fn has_clash(rows: &[&AssignmentRow]) -> bool {
+    rows.iter().enumerate().any(|(i, a)| {
+        rows[i + 1..]
+            .iter()
+            .any(|b| a.class_key() != b.class_key() && weeks_overlap(&a.week, &b.week))
+    })
+}
+
+/// Checa si el grupo tiene dos clases en el modulo en la misma semana
+/// Los subgrupos pueden compartir modulo entre ellos pero no con una clase de todo el grupo
+fn has_group_clash(rows: &[&AssignmentRow]) -> bool {
+    rows.iter().enumerate().any(|(i, a)| {
+        rows[i + 1..].iter().any(|b| {
+            weeks_overlap(&a.week, &b.week)
+                && (a.subgroup_id.is_none()
+                    || b.subgroup_id.is_none()
+                    || a.subgroup_id == b.subgroup_id)
+        })
+    })
+}
+
+/// Funcion para obtener todos los conflictos del horario actual
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Retorna un vector con los conflictos encontrados, vacio si el horario es valido
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_conflicts(pool: tauri::State<'_, AppState>) -> Result<Vec<Conflict>, String> {
+    find_conflicts(&pool.db).await
+}
+
+/// Busca los conflictos en la tabla de asignaciones
+pub async fn find_conflicts(pool: &DbPool) -> Result<Vec<Conflict>, String> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to get a connection: {}", e))?;
+
+    find_conflicts_in(&mut conn).await
+}
+
+/// Busca los conflictos usando una conexion ya abierta
+/// Permite revisar los cambios de una transaccion antes de confirmarla
+pub async fn find_conflicts_in(conn: &mut SqliteConnection) -> Result<Vec<Conflict>, String> {
+    let assignments: Vec<AssignmentRow> = sqlx::query_as::<_, AssignmentRow>(
+        "
+        SELECT id, group_id, day, module_index, teacher_id, classroom_id, subgroup_id, combined_id,
+               week
+        FROM assignments ORDER BY id
+        ",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to get assignments: {}", e))?;
+
+    let mut conflicts: Vec<Conflict> = Vec::new();
+
+    // Agrupar por grupo, profesor y salon en cada modulo
+    let mut by_group: BTreeMap<(i16, &str, i16), Vec<&AssignmentRow>> = BTreeMap::new();
+    let mut by_teacher: BTreeMap<(i16, &str, i16), Vec<&AssignmentRow>> = BTreeMap::new();
+    let mut by_classroom: BTreeMap<(i16, &str, i16), Vec<&AssignmentRow>> = BTreeMap::new();
+    for a in &assignments {
+        by_group
+            .entry((a.group_id, &a.day, a.module_index))
+            .or_default()
+            .push(a);
+        by_teacher
+            .entry((a.teacher_id, &a.day, a.module_index))
+            .or_default()
+            .push(a);
+        if let Some(classroom_id) = a.classroom_id {
+            by_classroom
+                .entry((classroom_id, &a.day, a.module_index))
+                .or_default()
+                .push(a);
+        }
+    }
+
+    for ((group_id, day, module_index), rows) in by_group {
+        if has_group_clash(&rows) {
+            conflicts.push(Conflict {
+                kind: ConflictKind::GroupDoubleBooked,
+                day: day.to_string(),
+                module_index,
+                group_id: Some(group_id),
+                teacher_id: None,
+                classroom_id: None,
+                assignment_ids: rows.iter().map(|a| a.id).collect(),
+            });
+        }
+    }
+
+    // Una clase compartida por varios grupos no cuenta como doble asignacion
+    for ((teacher_id, day, module_index), rows) in by_teacher {
+        if has_clash(&rows) {
+            conflicts.push(Conflict {
+                kind: ConflictKind::TeacherDoubleBooked,
+                day: day.to_string(),
+                module_index,
+                group_id: None,
+                teacher_id: Some(teacher_id),
+                classroom_id: None,
+                assignment_ids: rows.iter().map(|a| a.id).collect(),
+            });
+        }
+    }
+
+    for ((classroom_id, day, module_index), rows) in by_classroom {
+        if has_clash(&rows) {
+            conflicts.push(Conflict {
+                kind: ConflictKind::ClassroomDoubleBooked,
+                day: day.to_string(),
+                module_index,
+                group_id: None,
+                teacher_id: None,
+                classroom_id: Some(classroom_id),
+                assignment_ids: rows.iter().map(|a| a.id).collect(),
+            });
+        }
+    }
+
+    // Asignaciones en bloques donde el profesor no puede trabajar
+    let unavailable: Vec<(i16, i16, String, i16)> = sqlx::query_as(
+        "
+        SELECT a.id, a.teacher_id, a.day, a.module_index
+        FROM assignments a
+        JOIN teacher_unavailability u
+            ON u.teacher_id = a.teacher_id AND u.day = a.day AND u.module_index = a.module_index
+        ORDER BY a.id
+        ",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to check teacher unavailability: {}", e))?;
+
+    for (id, teacher_id, day, module_index) in unavailable {
+        conflicts.push(Conflict {
+            kind: ConflictKind::TeacherUnavailable,
+            day,
+            module_index,
+            group_id: None,
+            teacher_id: Some(teacher_id),
+            classroom_id: None,
+            assignment_ids: vec![id],
+        });
+    }
+
+    // Asignaciones en bloques donde el salon no se puede usar
+    let classrooms: Vec<Classroom> = sqlx::query_as::<_, Classroom>("SELECT * FROM classroom")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to get classrooms: {}", e))?;
+
+    for a in &assignments {
+        let Some(classroom_id) = a.classroom_id else {
+            continue;
+        };
+        let blocked = classrooms.iter().any(|c| {
+            c.id == Some(classroom_id)
+                && c.availability
+                    .iter()
+                    .any(|b| b.day == a.day && b.module_index == a.module_index)
+        });
+
+        if blocked {
+            conflicts.push(Conflict {
+                kind: ConflictKind::ClassroomUnavailable,
+                day: a.day.clone(),
+                module_index: a.module_index,
+                group_id: Some(a.group_id),
+                teacher_id: None,
+                classroom_id: Some(classroom_id),
+                assignment_ids: vec![a.id],
+            });
+        }
+    }
+
+    Ok(conflicts)
+}
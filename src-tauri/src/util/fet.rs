@@ -0,0 +1,798 @@
+use crate::class::classrooms::parse_availability;
+use crate::class::school_calendar::fetch_school_calendar;
+use crate::db::{AppState, DbPool};
+use crate::util::assignments::delete_empty_combined;
+use crate::util::calendar::{MODULES_PER_DAY, SCHOOL_DAYS};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Version de FET con la que se generan los archivos
+const FET_VERSION: &str = "6.9.0";
+
+/// Prefijo del comentario de las actividades que solo son en semana A o B (ej: `week:a`)
+const WEEK_COMMENT: &str = "week:";
+
+/// Resultado de importar un horario resuelto de FET
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetImport {
+    /// Asignaciones creadas
+    pub assignments: i64,
+    /// Actividades que no se importaron y el por que
+    pub skipped: Vec<String>,
+}
+
+/// Nombres unicos de los datos, FET identifica todo por nombre
+struct FetNames {
+    subjects: HashMap<i16, String>,
+    teachers: HashMap<i16, String>,
+    groups: HashMap<i16, String>,
+    rooms: HashMap<i16, String>,
+    subgroups: HashMap<i16, String>,
+}
+
+/// Asignaciones agrupadas: (grupo, materia, profesor, subgrupo, clase compartida, semana, modulos)
+type ScheduledRow = (i16, i16, i16, Option<i16>, Option<i16>, String, i64);
+
+/// Actividad de FET: una materia con sus alumnos que se reparte en varios modulos
+struct FetActivity {
+    subject_id: i16,
+    teacher_id: Option<i16>,
+    /// Nombres de los grupos o subgrupos que la toman, varios si es una clase compartida
+    students: Vec<String>,
+    /// Semana en la que se da (`all`, `a` o `b`)
+    week: String,
+    modules: i16,
+}
+
+impl FetNames {
+    async fn load(pool: &DbPool) -> Result<Self, String> {
+        Ok(FetNames {
+            subjects: fetch_names(pool, "SELECT id, name FROM subjects ORDER BY id").await?,
+            teachers: fetch_names(
+                pool,
+                "SELECT id, name || ' ' || father_lastname FROM teachers ORDER BY id",
+            )
+            .await?,
+            groups: fetch_names(
+                pool,
+                "SELECT id, grade || \"group\" FROM groups ORDER BY id",
+            )
+            .await?,
+            rooms: fetch_names(
+                pool,
+                "SELECT id, building_id || building_number FROM classroom ORDER BY id",
+            )
+            .await?,
+            subgroups: fetch_names(
+                pool,
+                "
+                SELECT s.id, g.grade || g.\"group\" || '-' || s.name
+                FROM subgroups s JOIN groups g ON g.id = s.group_id ORDER BY s.id
+                ",
+            )
+            .await?,
+        })
+    }
+}
+
+/// Funcion para exportar los datos a un archivo `.fet` para FET
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `path` - Ruta del archivo a crear
+/// Exporta materias, profesores (con sus materias y horas comisionadas), grupos con sus
+/// subgrupos, salones, una actividad por cada materia de cada grupo y como restricciones los
+/// bloques no disponibles de profesores y salones y los tipos de salon de cada materia
+/// El profesor de cada actividad es el del plan de carga, si no tiene es el que ya la tiene
+/// asignada o el primero que puede dar la materia
+/// Las materias que ya estan asignadas por subgrupo, como clase compartida o por semana A/B
+/// se exportan como estan en el horario. FET no tiene semanas A/B, esas actividades llevan
+/// el comentario `week:a` o `week:b` y FET las acomoda en modulos distintos
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn export_fet(pool: tauri::State<'_, AppState>, path: String) -> Result<(), String> {
+    let xml = build_fet(&pool.db).await?;
+    std::fs::write(&path, xml).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Funcion para importar un horario resuelto por FET
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `path` - Ruta del archivo `.fet` (ej: `*_data_and_timetable.fet`)
+/// Cada actividad con dia y hora se vuelve una asignacion por cada modulo que dura. Las
+/// materias, profesores, grupos, subgrupos y salones se buscan por el mismo nombre con el
+/// que se exportan. Las actividades de varios grupos se guardan como clases compartidas y
+/// las que tienen el comentario `week:a` o `week:b` solo en esa semana
+/// Reemplaza el horario de los grupos que aparecen en el archivo
+/// Retorna la cantidad de asignaciones creadas y las actividades que no se importaron
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn import_fet(
+    pool: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<FetImport, String> {
+    let xml =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    load_fet(&pool.db, &xml).await
+}
+
+/// Arma el XML de FET con los datos de la base de datos
+pub async fn build_fet(pool: &DbPool) -> Result<String, String> {
+    let names = FetNames::load(pool).await?;
+    let calendar = fetch_school_calendar(pool).await?;
+
+    // Las horas se nombran con la hora de inicio del modulo si el calendario la tiene
+    let hours: Vec<String> = (0..MODULES_PER_DAY)
+        .map(|m| {
+            calendar
+                .as_ref()
+                .and_then(|c| c.module_time(m))
+                .map(|t| t.start_time.clone())
+                .unwrap_or_else(|| (m + 1).to_string())
+        })
+        .collect();
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<fet version=\"{}\">\n", FET_VERSION));
+    xml.push_str("<Institution_Name>School Roster</Institution_Name>\n");
+
+    xml.push_str(&format!(
+        "<Days_List>\n<Number_of_Days>{}</Number_of_Days>\n",
+        SCHOOL_DAYS.len()
+    ));
+    for day in SCHOOL_DAYS {
+        xml.push_str(&format!("<Day><Name>{}</Name></Day>\n", escape_xml(day)));
+    }
+    xml.push_str("</Days_List>\n");
+
+    xml.push_str(&format!(
+        "<Hours_List>\n<Number_of_Hours>{}</Number_of_Hours>\n",
+        hours.len()
+    ));
+    for hour in &hours {
+        xml.push_str(&format!("<Hour><Name>{}</Name></Hour>\n", escape_xml(hour)));
+    }
+    xml.push_str("</Hours_List>\n");
+
+    xml.push_str("<Subjects_List>\n");
+    for name in sorted_values(&names.subjects) {
+        xml.push_str(&format!(
+            "<Subject><Name>{}</Name></Subject>\n",
+            escape_xml(name)
+        ));
+    }
+    xml.push_str("</Subjects_List>\n");
+
+    let teachers: Vec<(i16, Option<i16>)> =
+        sqlx::query_as("SELECT id, commisioned_hours FROM teachers ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get teachers: {}", e))?;
+    let qualified: Vec<(i16, i16)> =
+        sqlx::query_as("SELECT teacher_id, subject_id FROM teacher_subjects")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get teacher subjects: {}", e))?;
+
+    xml.push_str("<Teachers_List>\n");
+    for (id, commisioned_hours) in &teachers {
+        xml.push_str(&format!(
+            "<Teacher>\n<Name>{}</Name>\n<Target_Number_of_Hours>{}</Target_Number_of_Hours>\n<Qualified_Subjects>\n",
+            escape_xml(&names.teachers[id]),
+            commisioned_hours.unwrap_or(0)
+        ));
+        for (_, subject_id) in qualified.iter().filter(|(t, _)| t == id) {
+            xml.push_str(&format!(
+                "<Qualified_Subject>{}</Qualified_Subject>\n",
+                escape_xml(&names.subjects[subject_id])
+            ));
+        }
+        xml.push_str("</Qualified_Subjects>\n</Teacher>\n");
+    }
+    xml.push_str("</Teachers_List>\n");
+
+    // Los grupos se acomodan por grado (Year en FET)
+    let groups: Vec<(i16, i16, Option<i16>)> =
+        sqlx::query_as("SELECT id, grade, students FROM groups ORDER BY grade, \"group\"")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get groups: {}", e))?;
+    let subgroups: Vec<(i16, i16, Option<i16>)> =
+        sqlx::query_as("SELECT id, group_id, students FROM subgroups ORDER BY group_id, name")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get subgroups: {}", e))?;
+
+    xml.push_str("<Students_List>\n");
+    let mut grades: Vec<i16> = groups.iter().map(|(_, grade, _)| *grade).collect();
+    grades.dedup();
+    for grade in grades {
+        let members: Vec<&(i16, i16, Option<i16>)> =
+            groups.iter().filter(|(_, g, _)| *g == grade).collect();
+        let total: i16 = members.iter().map(|(_, _, s)| s.unwrap_or(0)).sum();
+        xml.push_str(&format!(
+            "<Year>\n<Name>{}</Name>\n<Number_of_Students>{}</Number_of_Students>\n",
+            grade, total
+        ));
+        for (id, _, students) in members {
+            xml.push_str(&format!(
+                "<Group>\n<Name>{}</Name>\n<Number_of_Students>{}</Number_of_Students>\n",
+                escape_xml(&names.groups[id]),
+                students.unwrap_or(0)
+            ));
+            for (subgroup_id, _, students) in subgroups.iter().filter(|(_, g, _)| g == id) {
+                xml.push_str(&format!(
+                    "<Subgroup><Name>{}</Name><Number_of_Students>{}</Number_of_Students></Subgroup>\n",
+                    escape_xml(&names.subgroups[subgroup_id]),
+                    students.unwrap_or(0)
+                ));
+            }
+            xml.push_str("</Group>\n");
+        }
+        xml.push_str("</Year>\n");
+    }
+    xml.push_str("</Students_List>\n");
+
+    // Materias de cada grupo con el profesor del plan de carga, del horario o que la puede dar
+    let sections: Vec<(i16, i16, i16, Option<i16>)> = sqlx::query_as(
+        "
+        SELECT gs.group_id, gs.subject_id, COALESCE(gs.hours, s.required_modules),
+            COALESCE(
+                c.teacher_id,
+                (SELECT a.teacher_id FROM assignments a
+                 WHERE a.group_id = gs.group_id AND a.subject_id = gs.subject_id
+                 ORDER BY a.id LIMIT 1),
+                (SELECT t.teacher_id FROM teacher_subjects t
+                 WHERE t.subject_id = gs.subject_id
+                 ORDER BY t.teacher_id LIMIT 1)
+            )
+        FROM groups_subjects gs
+        JOIN subjects s ON s.id = gs.subject_id
+        LEFT JOIN course_loads c ON c.group_id = gs.group_id AND c.subject_id = gs.subject_id
+        ORDER BY gs.group_id, gs.subject_id
+        ",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get group subjects: {}", e))?;
+
+    // Materias que ya estan asignadas por subgrupo, como clase compartida o por semana
+    let scheduled: Vec<ScheduledRow> = sqlx::query_as(
+        "
+        SELECT group_id, subject_id, teacher_id, subgroup_id, combined_id, week, COUNT(*)
+        FROM assignments
+        WHERE (group_id, subject_id) IN (
+            SELECT group_id, subject_id FROM assignments
+            WHERE subgroup_id IS NOT NULL OR combined_id IS NOT NULL OR week <> 'all'
+        )
+        GROUP BY group_id, subject_id, teacher_id, subgroup_id, combined_id, week
+        ORDER BY group_id, subject_id
+        ",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get scheduled activities: {}", e))?;
+
+    let mut activities: Vec<FetActivity> = Vec::new();
+    // (clase compartida, materia, profesor, semana) -> posicion de su actividad
+    let mut combined: HashMap<(i16, i16, i16, String), usize> = HashMap::new();
+    for (group_id, subject_id, teacher_id, subgroup_id, combined_id, week, modules) in &scheduled {
+        let students = match subgroup_id {
+            Some(subgroup_id) => names.subgroups[subgroup_id].clone(),
+            None => names.groups[group_id].clone(),
+        };
+        if let Some(combined_id) = combined_id {
+            let key = (*combined_id, *subject_id, *teacher_id, week.clone());
+            if let Some(&index) = combined.get(&key) {
+                let activity = &mut activities[index];
+                if !activity.students.contains(&students) {
+                    activity.students.push(students);
+                }
+                activity.modules = activity.modules.max(*modules as i16);
+                continue;
+            }
+            combined.insert(key, activities.len());
+        }
+        activities.push(FetActivity {
+            subject_id: *subject_id,
+            teacher_id: Some(*teacher_id),
+            students: vec![students],
+            week: week.clone(),
+            modules: *modules as i16,
+        });
+    }
+    for (group_id, subject_id, hours, teacher_id) in sections {
+        if scheduled
+            .iter()
+            .any(|s| s.0 == group_id && s.1 == subject_id)
+        {
+            continue;
+        }
+        activities.push(FetActivity {
+            subject_id,
+            teacher_id,
+            students: vec![names.groups[&group_id].clone()],
+            week: "all".to_string(),
+            modules: hours,
+        });
+    }
+
+    // Una actividad de FET por modulo de cada materia, ligadas por Activity_Group_Id
+    xml.push_str("<Activities_List>\n");
+    let mut activity_id = 0;
+    for activity in &activities {
+        let group_activity = activity_id + 1;
+        for _ in 0..activity.modules {
+            activity_id += 1;
+            xml.push_str("<Activity>\n");
+            if let Some(teacher_id) = activity.teacher_id {
+                xml.push_str(&format!(
+                    "<Teacher>{}</Teacher>\n",
+                    escape_xml(&names.teachers[&teacher_id])
+                ));
+            }
+            xml.push_str(&format!(
+                "<Subject>{}</Subject>\n",
+                escape_xml(&names.subjects[&activity.subject_id])
+            ));
+            for students in &activity.students {
+                xml.push_str(&format!("<Students>{}</Students>\n", escape_xml(students)));
+            }
+            xml.push_str(&format!(
+                "<Duration>1</Duration>\n<Total_Duration>{}</Total_Duration>\n<Id>{}</Id>\n<Activity_Group_Id>{}</Activity_Group_Id>\n<Active>true</Active>\n",
+                activity.modules,
+                activity_id,
+                if activity.modules > 1 { group_activity } else { 0 }
+            ));
+            if activity.week != "all" {
+                xml.push_str(&format!(
+                    "<Comments>{}{}</Comments>\n",
+                    WEEK_COMMENT, activity.week
+                ));
+            }
+            xml.push_str("</Activity>\n");
+        }
+    }
+    xml.push_str("</Activities_List>\n");
+
+    let rooms: Vec<(i16, Option<i16>)> =
+        sqlx::query_as("SELECT id, capacity FROM classroom ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get classrooms: {}", e))?;
+
+    xml.push_str("<Rooms_List>\n");
+    for (id, capacity) in &rooms {
+        xml.push_str(&format!(
+            "<Room><Name>{}</Name><Building></Building><Capacity>{}</Capacity><Virtual>false</Virtual></Room>\n",
+            escape_xml(&names.rooms[id]),
+            capacity.unwrap_or(0)
+        ));
+    }
+    xml.push_str("</Rooms_List>\n");
+
+    // Restricciones de tiempo: bloques no disponibles de los profesores
+    xml.push_str("<Time_Constraints_List>\n");
+    xml.push_str("<ConstraintBasicCompulsoryTime><Weight_Percentage>100</Weight_Percentage><Active>true</Active></ConstraintBasicCompulsoryTime>\n");
+    let unavailable: Vec<(i16, String, i16)> = sqlx::query_as(
+        "SELECT teacher_id, day, module_index FROM teacher_unavailability ORDER BY teacher_id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get teacher unavailability: {}", e))?;
+    for (id, _) in &teachers {
+        let blocks: Vec<(&str, i16)> = unavailable
+            .iter()
+            .filter(|(t, day, m)| {
+                t == id && SCHOOL_DAYS.contains(&day.as_str()) && *m < MODULES_PER_DAY
+            })
+            .map(|(_, day, m)| (day.as_str(), *m))
+            .collect();
+        xml.push_str(&not_available_constraint(
+            "ConstraintTeacherNotAvailableTimes",
+            "Teacher",
+            &names.teachers[id],
+            &blocks,
+            &hours,
+        ));
+    }
+    xml.push_str("</Time_Constraints_List>\n");
+
+    // Restricciones de espacio: bloques no disponibles de los salones
+    xml.push_str("<Space_Constraints_List>\n");
+    xml.push_str("<ConstraintBasicCompulsorySpace><Weight_Percentage>100</Weight_Percentage><Active>true</Active></ConstraintBasicCompulsorySpace>\n");
+    let availability: Vec<(i16, Option<String>)> =
+        sqlx::query_as("SELECT id, availability FROM classroom ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get classroom availability: {}", e))?;
+    for (id, json) in availability {
+        let blocks = parse_availability(id, json)?;
+        let blocks: Vec<(&str, i16)> = blocks
+            .iter()
+            .filter(|b| SCHOOL_DAYS.contains(&b.day.as_str()) && b.module_index < MODULES_PER_DAY)
+            .map(|b| (b.day.as_str(), b.module_index))
+            .collect();
+        xml.push_str(&not_available_constraint(
+            "ConstraintRoomNotAvailableTimes",
+            "Room",
+            &names.rooms[&id],
+            &blocks,
+            &hours,
+        ));
+    }
+
+    // Las materias con tipo de salon solo pueden ir en salones de ese tipo
+    let room_types: Vec<(i16, i16)> = sqlx::query_as(
+        "
+        SELECT DISTINCT r.subject_id, c.id
+        FROM subject_room_types r
+        JOIN classroom c ON LOWER(TRIM(c.building_type)) = LOWER(TRIM(r.room_type))
+        ORDER BY r.subject_id, c.id
+        ",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get subject room types: {}", e))?;
+    let mut typed_subjects: Vec<i16> = room_types.iter().map(|(s, _)| *s).collect();
+    typed_subjects.dedup();
+    for subject_id in typed_subjects {
+        let rooms: Vec<&String> = room_types
+            .iter()
+            .filter(|(s, _)| *s == subject_id)
+            .map(|(_, room_id)| &names.rooms[room_id])
+            .collect();
+        xml.push_str(&format!(
+            "<ConstraintSubjectPreferredRooms>\n<Weight_Percentage>100</Weight_Percentage>\n<Subject>{}</Subject>\n<Number_of_Preferred_Rooms>{}</Number_of_Preferred_Rooms>\n",
+            escape_xml(&names.subjects[&subject_id]),
+            rooms.len()
+        ));
+        for room in rooms {
+            xml.push_str(&format!(
+                "<Preferred_Room>{}</Preferred_Room>\n",
+                escape_xml(room)
+            ));
+        }
+        xml.push_str("<Active>true</Active>\n</ConstraintSubjectPreferredRooms>\n");
+    }
+    xml.push_str("</Space_Constraints_List>\n");
+    xml.push_str("</fet>\n");
+
+    Ok(xml)
+}
+
+/// Carga las actividades con dia y hora de un archivo de FET como asignaciones
+pub async fn load_fet(pool: &DbPool, xml: &str) -> Result<FetImport, String> {
+    let document =
+        roxmltree::Document::parse(xml).map_err(|e| format!("Invalid FET file: {}", e))?;
+    let root = document.root_element();
+    if root.tag_name().name() != "fet" {
+        return Err("The file is not a FET file".to_string());
+    }
+
+    let names = FetNames::load(pool).await?;
+    let find = |map: &HashMap<i16, String>, name: &str| {
+        map.iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(id, _)| *id)
+    };
+    let subgroup_groups: HashMap<i16, i16> =
+        sqlx::query_as::<_, (i16, i16)>("SELECT id, group_id FROM subgroups")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get subgroups: {}", e))?
+            .into_iter()
+            .collect();
+
+    let list = |tag: &str| -> Vec<String> {
+        root.children()
+            .find(|n| n.has_tag_name(tag))
+            .map(|list| {
+                list.children()
+                    .filter(|n| n.is_element())
+                    .filter_map(|n| child_text(n, "Name"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let days = list("Days_List");
+    let hours = list("Hours_List");
+
+    // Dia y hora de cada actividad y salon: Activity_Id -> valor
+    let mut times: HashMap<String, (String, String)> = HashMap::new();
+    let mut rooms: HashMap<String, String> = HashMap::new();
+    for node in root.descendants() {
+        if !node.has_tag_name("ConstraintActivityPreferredStartingTime")
+            && !node.has_tag_name("ConstraintActivityPreferredRoom")
+        {
+            continue;
+        }
+        let Some(activity_id) = child_text(node, "Activity_Id") else {
+            continue;
+        };
+        if node.has_tag_name("ConstraintActivityPreferredRoom") {
+            if let Some(room) = child_text(node, "Room") {
+                rooms.insert(activity_id, room);
+            }
+        } else if let (Some(day), Some(hour)) = (
+            child_text(node, "Preferred_Day"),
+            child_text(node, "Preferred_Hour"),
+        ) {
+            times.insert(activity_id, (day, hour));
+        }
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let mut skipped: Vec<String> = Vec::new();
+    let mut cleared: Vec<i16> = Vec::new();
+    let mut created = 0;
+
+    let activities = root.descendants().filter(|n| {
+        n.has_tag_name("Activity")
+            && n.parent()
+                .is_some_and(|p| p.has_tag_name("Activities_List"))
+    });
+    for activity in activities {
+        let id = child_text(activity, "Id").unwrap_or_default();
+        let Some((day, hour)) = times.get(&id) else {
+            skipped.push(format!("Activity {} has no time", id));
+            continue;
+        };
+
+        // Las horas se buscan por posicion porque pueden tener cualquier nombre
+        let day = SCHOOL_DAYS
+            .iter()
+            .find(|d| **d == day.as_str())
+            .or_else(|| {
+                days.iter()
+                    .position(|d| d == day)
+                    .and_then(|i| SCHOOL_DAYS.get(i))
+            });
+        let module_index = hours.iter().position(|h| h == hour).map(|m| m as i16);
+        let (Some(day), Some(module_index)) = (day, module_index) else {
+            skipped.push(format!("Activity {} has an unknown day or hour", id));
+            continue;
+        };
+
+        // Una actividad de varios modulos ocupa los modulos seguidos desde su hora
+        let duration = match child_text(activity, "Duration") {
+            Some(duration) => duration.parse::<i16>().unwrap_or(0),
+            None => 1,
+        };
+        if duration < 1 || module_index + duration > MODULES_PER_DAY {
+            skipped.push(format!("Activity {} does not fit in the school day", id));
+            continue;
+        }
+        let week = match child_text(activity, "Comments")
+            .as_deref()
+            .and_then(|c| c.strip_prefix(WEEK_COMMENT))
+        {
+            Some("a") => "a",
+            Some("b") => "b",
+            _ => "all",
+        };
+
+        let subject = child_text(activity, "Subject").unwrap_or_default();
+        let Some(subject_id) = find(&names.subjects, &subject) else {
+            skipped.push(format!("Activity {}: unknown subject '{}'", id, subject));
+            continue;
+        };
+        let Some(teacher) = child_text(activity, "Teacher") else {
+            skipped.push(format!("Activity {} has no teacher", id));
+            continue;
+        };
+        let Some(teacher_id) = find(&names.teachers, &teacher) else {
+            skipped.push(format!("Activity {}: unknown teacher '{}'", id, teacher));
+            continue;
+        };
+        // Los alumnos pueden ser un grupo o un subgrupo: (grupo, subgrupo)
+        let mut students: Vec<(i16, Option<i16>)> = Vec::new();
+        for node in activity.children().filter(|n| n.has_tag_name("Students")) {
+            let name = node.text().unwrap_or_default().trim();
+            if let Some(group_id) = find(&names.groups, name) {
+                students.push((group_id, None));
+            } else if let Some(subgroup_id) = find(&names.subgroups, name) {
+                students.push((subgroup_groups[&subgroup_id], Some(subgroup_id)));
+            } else {
+                skipped.push(format!("Activity {}: unknown group '{}'", id, name));
+            }
+        }
+        if students.is_empty() {
+            continue;
+        }
+        let classroom_id = rooms.get(&id).and_then(|room| find(&names.rooms, room));
+
+        // El horario anterior de cada grupo se borra la primera vez que aparece
+        for (group_id, _) in &students {
+            if !cleared.contains(group_id) {
+                sqlx::query("DELETE FROM assignments WHERE group_id = ?1")
+                    .bind(group_id)
+                    .execute(&mut tx)
+                    .await
+                    .map_err(|e| format!("Failed to clear group assignments: {}", e))?;
+                cleared.push(*group_id);
+            }
+        }
+
+        let combined_id: Option<i16> = if students.len() > 1 {
+            Some(
+                sqlx::query_scalar("INSERT INTO combined_classes (name) VALUES (?1) RETURNING id")
+                    .bind(&subject)
+                    .fetch_one(&mut tx)
+                    .await
+                    .map_err(|e| format!("Failed to create combined class: {}", e))?,
+            )
+        } else {
+            None
+        };
+
+        for (group_id, subgroup_id) in students {
+            for offset in 0..duration {
+                sqlx::query(
+                    "
+                    INSERT INTO assignments
+                        (group_id, day, module_index, subject_id, teacher_id, classroom_id,
+                         combined_id, subgroup_id, week)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                    ",
+                )
+                .bind(group_id)
+                .bind(day)
+                .bind(module_index + offset)
+                .bind(subject_id)
+                .bind(teacher_id)
+                .bind(classroom_id)
+                .bind(combined_id)
+                .bind(subgroup_id)
+                .bind(week)
+                .execute(&mut tx)
+                .await
+                .map_err(|e| format!("Failed to import activity {}: {}", id, e))?;
+                created += 1;
+            }
+        }
+    }
+
+    // Las clases compartidas de los horarios borrados ya no tienen asignaciones
+    delete_empty_combined(&mut tx).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(FetImport {
+        assignments: created,
+        skipped,
+    })
+}
+
+/// Arma una restriccion de tiempos no disponibles de un profesor o salon
+fn not_available_constraint(
+    tag: &str,
+    owner_tag: &str,
+    owner: &str,
+    blocks: &[(&str, i16)],
+    hours: &[String],
+) -> String {
+    if blocks.is_empty() {
+        return String::new();
+    }
+
+    let mut xml = format!(
+        "<{}>\n<Weight_Percentage>100</Weight_Percentage>\n<{}>{}</{}>\n<Number_of_Not_Available_Times>{}</Number_of_Not_Available_Times>\n",
+        tag,
+        owner_tag,
+        escape_xml(owner),
+        owner_tag,
+        blocks.len()
+    );
+    for (day, module_index) in blocks {
+        xml.push_str(&format!(
+            "<Not_Available_Time><Day>{}</Day><Hour>{}</Hour></Not_Available_Time>\n",
+            escape_xml(day),
+            escape_xml(&hours[*module_index as usize])
+        ));
+    }
+    xml.push_str(&format!("<Active>true</Active>\n</{}>\n", tag));
+    xml
+}
+
+/// Obtiene (id, nombre) de una consulta con nombres unicos
+async fn fetch_names(pool: &DbPool, query: &str) -> Result<HashMap<i16, String>, String> {
+    let rows: Vec<(i16, Option<String>)> = sqlx::query_as(query)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to get names: {}", e))?;
+
+    Ok(unique_names(&rows))
+}
+
+/// Agrega el ID a los nombres repetidos o vacios para que sean unicos
+fn unique_names(rows: &[(i16, Option<String>)]) -> HashMap<i16, String> {
+    let mut names: HashMap<i16, String> = HashMap::new();
+    for (id, name) in rows {
+        let name = name.clone().unwrap_or_default().trim().to_string();
+        let repeated = rows
+            .iter()
+            .filter(|(_, n)| n.clone().unwrap_or_default().trim() == name)
+            .count()
+            > 1;
+        let name = if repeated || name.is_empty() {
+            format!("{} #{}", name, id).trim().to_string()
+        } else {
+            name
+        };
+        names.insert(*id, name);
+    }
+
+    names
+}
+
+/// Valores de un mapa ordenados por llave
+fn sorted_values(map: &HashMap<i16, String>) -> Vec<&String> {
+    let mut entries: Vec<(&i16, &String)> = map.iter().collect();
+    entries.sort();
+    entries.into_iter().map(|(_, v)| v).collect()
+}
+
+/// Texto de un hijo de un nodo XML (ej: <Name>...</Name>)
+fn child_text(node: roxmltree::Node, tag: &str) -> Option<String> {
+    node.children()
+        .find(|n| n.has_tag_name(tag))
+        .and_then(|n| n.text())
+        .map(|t| t.trim().to_string())
+}
+
+/// Escapa los caracteres especiales de XML
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_names_keeps_distinct_names() {
+        let names = unique_names(&[(1, Some(" 1A ".to_string())), (2, Some("1B".to_string()))]);
+        assert_eq!(names[&1], "1A");
+        assert_eq!(names[&2], "1B");
+    }
+
+    #[test]
+    fn unique_names_adds_id_to_repeated_names() {
+        // Se comparan sin espacios al inicio y al final
+        let names = unique_names(&[
+            (1, Some("Ana Lopez".to_string())),
+            (2, Some("Ana Lopez ".to_string())),
+            (3, Some("Luis Perez".to_string())),
+        ]);
+        assert_eq!(names[&1], "Ana Lopez #1");
+        assert_eq!(names[&2], "Ana Lopez #2");
+        assert_eq!(names[&3], "Luis Perez");
+    }
+
+    #[test]
+    fn unique_names_names_empty_values_by_id() {
+        let names = unique_names(&[(4, None), (5, Some("  ".to_string()))]);
+        assert_eq!(names[&4], "#4");
+        assert_eq!(names[&5], "#5");
+    }
+
+    #[test]
+    fn escape_xml_escapes_special_characters() {
+        assert_eq!(
+            escape_xml("A&B <\"x\"> 'y'"),
+            "A&amp;B &lt;&quot;x&quot;&gt; &apos;y&apos;"
+        );
+    }
+}
@@ -0,0 +1,854 @@
+use crate::class::school_calendar::{fetch_school_calendar, SchoolCalendar};
+use crate::db::{AppState, DbPool};
+use crate::util::calendar::{parse_date, parse_time, week_of, SCHOOL_DAYS, WEEK_DAYS};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Horario que se exporta a iCalendar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IcalTarget {
+    Teacher,
+    Group,
+    Classroom,
+}
+
+impl IcalTarget {
+    fn name(&self) -> &'static str {
+        match self {
+            IcalTarget::Teacher => "teacher",
+            IcalTarget::Group => "group",
+            IcalTarget::Classroom => "classroom",
+        }
+    }
+
+    /// Columna de `assignments` que identifica al horario
+    fn column(&self) -> &'static str {
+        match self {
+            IcalTarget::Teacher => "a.teacher_id",
+            IcalTarget::Group => "a.group_id",
+            IcalTarget::Classroom => "a.classroom_id",
+        }
+    }
+}
+
+/// Clase del horario con los nombres que se muestran en el evento
+#[derive(Debug, FromRow)]
+struct IcalRow {
+    id: i16,
+    combined_id: Option<i16>,
+    day: String,
+    module_index: i16,
+    week: String,
+    subject: String,
+    teacher: String,
+    group_name: String,
+    room: Option<String>,
+}
+
+/// Evento leido de un archivo `.ics`
+#[derive(Debug, Default)]
+struct IcsEvent {
+    summary: Option<String>,
+    start: Option<NaiveDateTime>,
+    end: Option<NaiveDateTime>,
+    /// El evento dura todo el dia (fechas sin hora)
+    all_day: bool,
+    /// Las horas vienen en la zona horaria `TZID` del archivo y no se convierten
+    zoned: bool,
+    /// Duracion del evento cuando no trae `DTEND`
+    duration: Option<Duration>,
+    rrule: Option<String>,
+}
+
+/// Resultado de importar un archivo `.ics`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IcsImport {
+    /// Bloques (dia, modulo) marcados como no disponibles
+    pub blocks: Vec<(String, i16)>,
+    /// Eventos que no se repiten cada semana, estan fuera del periodo o solo caen en fin de
+    /// semana, no se importan
+    pub skipped_events: i16,
+    /// Eventos importados de forma aproximada: con zona horaria `TZID` (se toma la hora tal
+    /// como viene), que se repiten cada dos o mas semanas o que terminan antes del fin del
+    /// periodo (se bloquean todas las semanas)
+    pub approximate_events: i16,
+}
+
+/// Funcion para exportar el horario de un profesor, grupo o salon a un archivo `.ics`
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `target` - Tipo de horario (`teacher`, `group` o `classroom`)
+/// * `id` - ID del profesor, grupo o salon
+/// * `path` - Ruta del archivo a crear
+/// Cada clase es un evento semanal (o cada dos semanas si es de semana A o B) entre las
+/// fechas del periodo, con las horas de los modulos del calendario escolar
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn export_ical(
+    pool: tauri::State<'_, AppState>,
+    target: IcalTarget,
+    id: i16,
+    path: String,
+) -> Result<(), String> {
+    let calendar = required_calendar(&pool.db).await?;
+    let ics = build_ical(&pool.db, &calendar, target, id).await?;
+
+    std::fs::write(&path, ics).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Funcion para exportar el horario de todos los profesores, un archivo `.ics` por profesor
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `directory` - Carpeta donde se crean los archivos
+/// Solo se exportan los profesores que tienen clases asignadas
+/// Retorna las rutas de los archivos creados
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn export_teachers_ical(
+    pool: tauri::State<'_, AppState>,
+    directory: String,
+) -> Result<Vec<String>, String> {
+    let calendar = required_calendar(&pool.db).await?;
+
+    let teachers: Vec<(i16, String, String)> = sqlx::query_as(
+        "
+        SELECT id, name, father_lastname FROM teachers
+        WHERE EXISTS (SELECT 1 FROM assignments a WHERE a.teacher_id = teachers.id)
+        ORDER BY father_lastname, name
+        ",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get teachers: {}", e))?;
+
+    let mut files: Vec<String> = Vec::new();
+    for (id, name, father_lastname) in teachers {
+        let ics = build_ical(&pool.db, &calendar, IcalTarget::Teacher, id).await?;
+        let file_name: String = format!("{}_{}_{}.ics", id, father_lastname, name)
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path = Path::new(&directory).join(file_name);
+
+        std::fs::write(&path, ics)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        files.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(files)
+}
+
+/// Funcion para importar los horarios ocupados de un profesor desde un archivo `.ics`
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `teacher_id` - ID del profesor
+/// * `path` - Ruta del archivo `.ics` (ej: calendario de su otro trabajo)
+/// Los eventos que se repiten cada semana se vuelven bloques no disponibles del profesor
+/// en los modulos que se enciman con el evento segun las horas del calendario escolar.
+/// Las horas UTC se convierten a la hora local, las de otra zona (`TZID`) se toman tal como
+/// vienen. Solo se bloquean los dias de clases. Los bloques que el profesor ya tenia no se
+/// modifican
+/// Retorna los bloques agregados y la cantidad de eventos que no se importaron o se
+/// importaron de forma aproximada
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_teacher_ics(
+    pool: tauri::State<'_, AppState>,
+    teacher_id: i16,
+    path: String,
+) -> Result<IcsImport, String> {
+    let calendar = required_calendar(&pool.db).await?;
+    let term_start = parse_date(&calendar.term_start)?;
+    let term_end = parse_date(&calendar.term_end)?;
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let mut modules: Vec<(i16, NaiveTime, NaiveTime)> = Vec::new();
+    for module in &calendar.modules {
+        modules.push((
+            module.module_index,
+            parse_time(&module.start_time)?,
+            parse_time(&module.end_time)?,
+        ));
+    }
+
+    let mut blocks: Vec<(String, i16, Option<String>)> = Vec::new();
+    let mut skipped_events = 0;
+    let mut approximate_events = 0;
+    for event in parse_ics(&content) {
+        let Some((days, approximate)) = event_days(&event, term_start, term_end) else {
+            skipped_events += 1;
+            continue;
+        };
+        if approximate {
+            approximate_events += 1;
+        }
+
+        let Some(start) = event.start else {
+            continue;
+        };
+        let end = event.end.unwrap_or(start);
+
+        for day in days {
+            for (module_index, module_start, module_end) in &modules {
+                let overlaps =
+                    event.all_day || (*module_start < end.time() && start.time() < *module_end);
+                if overlaps && !blocks.iter().any(|(d, m, _)| d == day && m == module_index) {
+                    blocks.push((day.to_string(), *module_index, event.summary.clone()));
+                }
+            }
+        }
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    // Los bloques que ya existian conservan la razon que escribio el usuario
+    let mut added: Vec<(String, i16)> = Vec::new();
+    for (day, module_index, summary) in blocks {
+        let inserted = sqlx::query(
+            "
+            INSERT INTO teacher_unavailability (teacher_id, day, module_index, reason)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT (teacher_id, day, module_index) DO NOTHING
+            ",
+        )
+        .bind(teacher_id)
+        .bind(&day)
+        .bind(module_index)
+        .bind(summary.unwrap_or_else(|| "Calendario externo".to_string()))
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to add teacher unavailability: {}", e))?;
+
+        if inserted.rows_affected() > 0 {
+            added.push((day, module_index));
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(IcsImport {
+        blocks: added,
+        skipped_events,
+        approximate_events,
+    })
+}
+
+/// Arma el contenido `.ics` del horario de un profesor, grupo o salon
+pub async fn build_ical(
+    pool: &DbPool,
+    calendar: &SchoolCalendar,
+    target: IcalTarget,
+    id: i16,
+) -> Result<String, String> {
+    let term_start = parse_date(&calendar.term_start)?;
+    let term_end = parse_date(&calendar.term_end)?;
+    let week_a = match &calendar.week_a_start {
+        Some(date) => parse_date(date)?,
+        None => term_start,
+    };
+
+    let rows: Vec<IcalRow> = sqlx::query_as::<_, IcalRow>(&format!(
+        "
+        SELECT a.id, a.combined_id, a.day, a.module_index, a.week,
+               s.name AS subject,
+               t.name || ' ' || t.father_lastname AS teacher,
+               g.grade || g.\"group\" AS group_name,
+               c.building_id || c.building_number AS room
+        FROM assignments a
+        JOIN subjects s ON s.id = a.subject_id
+        JOIN teachers t ON t.id = a.teacher_id
+        JOIN groups g ON g.id = a.group_id
+        LEFT JOIN classroom c ON c.id = a.classroom_id
+        WHERE {} = ?1
+        ORDER BY a.id
+        ",
+        target.column()
+    ))
+    .bind(id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get assignments: {}", e))?;
+
+    // Una clase compartida es un solo evento con todos sus grupos
+    let mut classes: BTreeMap<i32, (IcalRow, Vec<String>)> = BTreeMap::new();
+    for row in rows {
+        let key = match row.combined_id {
+            Some(combined_id) => -(combined_id as i32),
+            None => row.id as i32,
+        };
+        let group_name = row.group_name.clone();
+        classes
+            .entry(key)
+            .or_insert_with(|| (row, Vec::new()))
+            .1
+            .push(group_name);
+    }
+
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//School Roster//Horario//ES".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for (key, (row, groups)) in classes {
+        let module = calendar.module_time(row.module_index).ok_or_else(|| {
+            format!(
+                "Module {} has no time in the school calendar",
+                row.module_index
+            )
+        })?;
+        let Some(date) = first_class_date(term_start, week_a, &row.day, &row.week) else {
+            continue;
+        };
+        if date > term_end {
+            continue;
+        }
+
+        let start = date.and_time(parse_time(&module.start_time)?);
+        let end = date.and_time(parse_time(&module.end_time)?);
+        let interval = if row.week == "all" { 1 } else { 2 };
+
+        let mut description = format!("Profesor: {}\nGrupo: {}", row.teacher, groups.join(", "));
+        if row.week != "all" {
+            description.push_str(&format!("\nSemana: {}", row.week.to_uppercase()));
+        }
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:class-{}-{}-{}@school-roster",
+            key,
+            target.name(),
+            id
+        ));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+        lines.push(format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")));
+        lines.push(format!(
+            "RRULE:FREQ=WEEKLY;INTERVAL={};UNTIL={}T235959",
+            interval,
+            term_end.format("%Y%m%d")
+        ));
+        lines.push(format!("SUMMARY:{}", escape_text(&row.subject)));
+        if let Some(room) = &row.room {
+            lines.push(format!("LOCATION:{}", escape_text(room)));
+        }
+        lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    // El formato pide lineas terminadas en CRLF y de a lo mas 75 bytes
+    Ok(lines
+        .iter()
+        .map(|l| fold_line(l))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n")
+}
+
+/// Obtiene el calendario escolar, es necesario para saber las fechas y horas de las clases
+async fn required_calendar(pool: &DbPool) -> Result<SchoolCalendar, String> {
+    fetch_school_calendar(pool)
+        .await?
+        .ok_or_else(|| "The school calendar has not been defined".to_string())
+}
+
+/// Regresa la primera fecha del periodo en la que toca la clase
+/// Para las clases de semana A o B se toma en cuenta la semana de `week_a`
+fn first_class_date(
+    term_start: NaiveDate,
+    week_a: NaiveDate,
+    day: &str,
+    week: &str,
+) -> Option<NaiveDate> {
+    let weekday = WEEK_DAYS.iter().position(|d| *d == day)? as i64;
+    let offset = (weekday - term_start.weekday().num_days_from_monday() as i64).rem_euclid(7);
+    let mut date = term_start + Duration::days(offset);
+
+    if week != "all" && week_of(date, week_a) != week {
+        date += Duration::days(7);
+    }
+
+    Some(date)
+}
+
+/// Codigos de los dias en iCalendar, en el mismo orden que `WEEK_DAYS`
+const ICS_DAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// Dias de clases que bloquea un evento que se repite cada semana o cada dia
+/// Regresa nulo si el evento no se repite asi, termina antes del periodo, empieza despues
+/// o solo cae en fin de semana. Tambien regresa si el bloqueo es aproximado
+fn event_days(
+    event: &IcsEvent,
+    term_start: NaiveDate,
+    term_end: NaiveDate,
+) -> Option<(Vec<&'static str>, bool)> {
+    let (Some(start), Some(rrule)) = (event.start, &event.rrule) else {
+        return None;
+    };
+    let rule = |name: &str| {
+        rrule
+            .split(';')
+            .find_map(|part| part.strip_prefix(&format!("{}=", name)))
+    };
+
+    let daily = match rule("FREQ") {
+        Some("DAILY") => true,
+        Some("WEEKLY") => false,
+        _ => return None,
+    };
+    let interval = rule("INTERVAL")
+        .and_then(|i| i.parse::<u16>().ok())
+        .map_or(1, |i| i64::from(i.max(1)));
+
+    // Dias de la semana del evento, si no hay BYDAY es el dia en que empieza
+    let days: Vec<&'static str> = match rule("BYDAY") {
+        Some(by_day) => by_day
+            .split(',')
+            .filter_map(|d| {
+                let code = d.trim_start_matches(|c: char| c == '-' || c.is_ascii_digit());
+                ICS_DAYS
+                    .iter()
+                    .position(|c| *c == code)
+                    .map(|i| WEEK_DAYS[i])
+            })
+            .collect(),
+        None if daily => WEEK_DAYS.to_vec(),
+        None => vec![WEEK_DAYS[start.weekday().num_days_from_monday() as usize]],
+    };
+
+    // Ultimo dia del evento: con COUNT se calcula hasta el final de la ultima semana
+    let last = match (rule("UNTIL").and_then(parse_ics_datetime), rule("COUNT")) {
+        (Some((until, _)), _) => Some(until.date()),
+        (None, Some(count)) => {
+            let count = i64::from(count.parse::<u16>().ok()?.max(1));
+            let offset = if daily && rule("BYDAY").is_none() {
+                Duration::days((count - 1) * interval)
+            } else {
+                let per_week = days.len().max(1) as i64;
+                let weeks = (count + per_week - 1) / per_week;
+                Duration::weeks((weeks - 1) * interval) + Duration::days(6)
+            };
+            start.date().checked_add_signed(offset)
+        }
+        (None, None) => None,
+    };
+    if last.is_some_and(|last| last < term_start) || start.date() > term_end {
+        return None;
+    }
+
+    let days: Vec<&'static str> = days
+        .into_iter()
+        .filter(|day| SCHOOL_DAYS.contains(day))
+        .collect();
+    if days.is_empty() {
+        return None;
+    }
+
+    // Un bloque no disponible es para todas las semanas del periodo
+    let approximate = event.zoned || interval > 1 || last.is_some_and(|last| last < term_end);
+    Some((days, approximate))
+}
+
+/// Lee los eventos de un archivo `.ics`
+fn parse_ics(content: &str) -> Vec<IcsEvent> {
+    // Las lineas que empiezan con espacio o tabulador continuan la anterior
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')) {
+            Some(rest) if !lines.is_empty() => lines.last_mut().unwrap().push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events: Vec<IcsEvent> = Vec::new();
+    let mut current: Option<IcsEvent> = None;
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = key.split(';').next().unwrap_or_default().to_uppercase();
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => current = Some(IcsEvent::default()),
+            ("END", "VEVENT") => {
+                if let Some(mut event) = current.take() {
+                    if event.end.is_none() {
+                        event.end = event
+                            .start
+                            .zip(event.duration)
+                            .and_then(|(start, duration)| start.checked_add_signed(duration));
+                    }
+                    events.push(event);
+                }
+            }
+            _ => {
+                let Some(event) = current.as_mut() else {
+                    continue;
+                };
+                match name.as_str() {
+                    "SUMMARY" => event.summary = Some(unescape_text(value)),
+                    "RRULE" => event.rrule = Some(value.to_uppercase()),
+                    "DTSTART" => {
+                        if let Some((start, all_day)) = parse_ics_datetime(value) {
+                            event.start = Some(start);
+                            event.all_day = all_day;
+                            event.zoned = key.to_uppercase().contains(";TZID=");
+                        }
+                    }
+                    "DTEND" => event.end = parse_ics_datetime(value).map(|(end, _)| end),
+                    "DURATION" => event.duration = parse_ics_duration(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    events
+}
+
+/// Convierte una fecha de iCalendar (`20240910T070000`, `20240910T070000Z` o `20240910`)
+/// Las horas UTC (con `Z`) se convierten a la hora local de la computadora
+/// Regresa la fecha y si es de todo el dia (sin hora)
+fn parse_ics_datetime(value: &str) -> Option<(NaiveDateTime, bool)> {
+    parse_ics_datetime_in(value, &Local)
+}
+
+/// Igual que `parse_ics_datetime` pero convierte las horas UTC a la zona `tz`
+fn parse_ics_datetime_in<Tz: TimeZone>(value: &str, tz: &Tz) -> Option<(NaiveDateTime, bool)> {
+    let value = value.trim();
+    if let Some(utc) = value.strip_suffix('Z') {
+        let date_time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        let local = Utc.from_utc_datetime(&date_time).with_timezone(tz);
+        return Some((local.naive_local(), false));
+    }
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Some((date_time, false));
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .ok()
+        .map(|date| (date.and_time(NaiveTime::MIN), true))
+}
+
+/// Convierte una duracion de iCalendar (ej: `PT1H30M`, `P1D` o `P1W`)
+fn parse_ics_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let value = value.strip_prefix('P')?;
+
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if number.is_empty() => in_time = true,
+            _ => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let part = match (c, in_time) {
+                    ('W', false) => Duration::try_weeks(n),
+                    ('D', false) => Duration::try_days(n),
+                    ('H', true) => Duration::try_hours(n),
+                    ('M', true) => Duration::try_minutes(n),
+                    ('S', true) => Duration::try_seconds(n),
+                    _ => None,
+                };
+                duration = duration.checked_add(&part?)?;
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+
+    Some(if negative { -duration } else { duration })
+}
+
+/// Quita el escape de los caracteres especiales de un texto de iCalendar
+/// Se lee de izquierda a derecha para que `\\n` sea una diagonal seguida de `n`
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(escaped @ (',' | ';' | '\\')) => unescaped.push(escaped),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Escapa los caracteres especiales de un texto de iCalendar
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Parte las lineas de mas de 75 bytes, la continuacion empieza con un espacio
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    #[test]
+    fn first_class_date_finds_the_first_weekday_of_the_term() {
+        // 2024-09-02 es lunes
+        let monday = date("2024-09-02");
+        assert_eq!(
+            first_class_date(monday, monday, "Miercoles", "all"),
+            Some(date("2024-09-04"))
+        );
+        assert_eq!(
+            first_class_date(monday, monday, "Lunes", "all"),
+            Some(date("2024-09-02"))
+        );
+
+        // Si el periodo empieza en miercoles el lunes es el de la semana siguiente
+        let wednesday = date("2024-09-04");
+        assert_eq!(
+            first_class_date(wednesday, wednesday, "Lunes", "all"),
+            Some(date("2024-09-09"))
+        );
+        assert_eq!(first_class_date(monday, monday, "Feriado", "all"), None);
+    }
+
+    #[test]
+    fn first_class_date_follows_the_a_b_weeks() {
+        let term_start = date("2024-09-02");
+        assert_eq!(
+            first_class_date(term_start, term_start, "Martes", "a"),
+            Some(date("2024-09-03"))
+        );
+        assert_eq!(
+            first_class_date(term_start, term_start, "Martes", "b"),
+            Some(date("2024-09-10"))
+        );
+
+        // La primera semana del periodo es B
+        let week_a = date("2024-09-11");
+        assert_eq!(
+            first_class_date(term_start, week_a, "Martes", "a"),
+            Some(date("2024-09-10"))
+        );
+        assert_eq!(
+            first_class_date(term_start, week_a, "Martes", "b"),
+            Some(date("2024-09-03"))
+        );
+    }
+
+    #[test]
+    fn fold_line_keeps_short_lines() {
+        assert_eq!(fold_line("SUMMARY:Matematicas"), "SUMMARY:Matematicas");
+        let exact = "X".repeat(75);
+        assert_eq!(fold_line(&exact), exact);
+    }
+
+    #[test]
+    fn fold_line_splits_at_75_bytes_without_breaking_characters() {
+        let line = format!("DESCRIPTION:{}", "Educación física, ".repeat(10));
+        let folded = fold_line(&line);
+
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75, "line too long: {:?}", part);
+        }
+        assert!(folded.split("\r\n").skip(1).all(|p| p.starts_with(' ')));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn parse_ics_datetime_reads_dates_and_times() {
+        let local = NaiveDateTime::parse_from_str("20240910T070000", "%Y%m%dT%H%M%S").unwrap();
+        assert_eq!(parse_ics_datetime("20240910T070000"), Some((local, false)));
+        assert_eq!(
+            parse_ics_datetime("20240910"),
+            Some((date("2024-09-10").and_time(NaiveTime::MIN), true))
+        );
+        assert_eq!(parse_ics_datetime("mañana"), None);
+    }
+
+    #[test]
+    fn parse_ics_datetime_converts_utc_to_the_given_zone() {
+        // Hora del centro de Mexico (UTC-6)
+        let zone = FixedOffset::west_opt(6 * 3600).unwrap();
+        let local = NaiveDateTime::parse_from_str("20240910T070000", "%Y%m%dT%H%M%S").unwrap();
+        assert_eq!(
+            parse_ics_datetime_in("20240910T130000Z", &zone),
+            Some((local, false))
+        );
+    }
+
+    #[test]
+    fn parse_ics_duration_reads_weeks_days_and_times() {
+        assert_eq!(parse_ics_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_ics_duration("P1DT2H"), Some(Duration::hours(26)));
+        assert_eq!(parse_ics_duration("P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_ics_duration("-PT15M"), Some(Duration::minutes(-15)));
+        assert_eq!(parse_ics_duration("P1H"), None);
+        assert_eq!(parse_ics_duration("PT5"), None);
+        assert_eq!(parse_ics_duration("1H"), None);
+        assert_eq!(parse_ics_duration("P99999999999999W"), None);
+    }
+
+    #[test]
+    fn unescape_text_reads_escapes_left_to_right() {
+        assert_eq!(unescape_text("C:\\\\new"), "C:\\new");
+        assert_eq!(
+            unescape_text("Aula 3\\, edificio B\\nPiso 2"),
+            "Aula 3, edificio B\nPiso 2"
+        );
+        assert_eq!(unescape_text("a\\;b\\x"), "a;b\\x");
+    }
+
+    fn event(start: &str, rrule: &str) -> IcsEvent {
+        IcsEvent {
+            start: parse_ics_datetime(start).map(|(start, _)| start),
+            rrule: Some(rrule.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn event_days_blocks_only_school_days() {
+        let (start, end) = (date("2024-09-02"), date("2024-12-20"));
+
+        let daily = event_days(&event("20240902T070000", "FREQ=DAILY"), start, end);
+        assert_eq!(daily, Some((SCHOOL_DAYS.to_vec(), false)));
+
+        let weekend = event("20240907T070000", "FREQ=WEEKLY;BYDAY=SA,SU");
+        assert_eq!(event_days(&weekend, start, end), None);
+
+        let monthly = event("20240902T070000", "FREQ=MONTHLY");
+        assert_eq!(event_days(&monthly, start, end), None);
+    }
+
+    #[test]
+    fn event_days_uses_count_to_find_the_last_date() {
+        let (start, end) = (date("2024-09-02"), date("2024-12-20"));
+
+        // Seis clases los lunes y miercoles desde agosto: termina antes del periodo
+        let before = event("20240805T070000", "FREQ=WEEKLY;BYDAY=MO,WE;COUNT=6");
+        assert_eq!(event_days(&before, start, end), None);
+
+        // Termina a mitad del periodo, se bloquea de forma aproximada
+        let partial = event("20240826T070000", "FREQ=WEEKLY;BYDAY=MO,WE;COUNT=6");
+        assert_eq!(
+            event_days(&partial, start, end),
+            Some((vec!["Lunes", "Miercoles"], true))
+        );
+
+        let daily = event("20240820T070000", "FREQ=DAILY;COUNT=10");
+        assert_eq!(event_days(&daily, start, end), None);
+    }
+
+    #[test]
+    fn parse_ics_uses_the_duration_when_there_is_no_end() {
+        let content = "BEGIN:VEVENT\n\
+            DTSTART:20240902T070000\n\
+            DURATION:PT1H30M\n\
+            END:VEVENT\n";
+        let events = parse_ics(content);
+        assert_eq!(
+            events[0].end.map(|e| e.time()),
+            NaiveTime::from_hms_opt(8, 30, 0)
+        );
+    }
+
+    #[test]
+    fn parse_ics_reads_events_with_folded_lines() {
+        let content = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Clase en otro\r\n  plantel\\, turno matutino\r\n\
+            DTSTART;TZID=America/Mexico_City:20240902T070000\r\n\
+            DTEND;TZID=America/Mexico_City:20240902T090000\r\n\
+            RRULE:FREQ=WEEKLY;byday=MO,WE\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Congreso\r\n\
+            DTSTART;VALUE=DATE:20241010\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let events = parse_ics(content);
+        assert_eq!(events.len(), 2);
+
+        let class = &events[0];
+        assert_eq!(
+            class.summary.as_deref(),
+            Some("Clase en otro plantel, turno matutino")
+        );
+        assert_eq!(class.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO,WE"));
+        assert_eq!(
+            class.start.map(|s| s.time()),
+            NaiveTime::from_hms_opt(7, 0, 0)
+        );
+        assert_eq!(
+            class.end.map(|e| e.time()),
+            NaiveTime::from_hms_opt(9, 0, 0)
+        );
+        assert!(class.zoned);
+        assert!(!class.all_day);
+
+        let congress = &events[1];
+        assert!(congress.all_day);
+        assert!(!congress.zoned);
+        assert!(congress.rrule.is_none());
+        assert!(congress.end.is_none());
+    }
+
+    #[test]
+    fn parse_ics_ignores_properties_outside_events() {
+        let content = "BEGIN:VCALENDAR\nSUMMARY:Calendario\nEND:VCALENDAR\n";
+        assert!(parse_ics(content).is_empty());
+    }
+}
@@ -0,0 +1,535 @@
+use crate::class::school_calendar::fetch_school_calendar;
+use crate::db::{AppState, DbPool};
+use crate::util::calendar::SCHOOL_DAYS;
+use crate::util::ical::{build_ical, IcalTarget};
+use crate::util::site::{escape_html, hex_rgb, module_labels, text_color};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use printpdf::path::PaintMode;
+use printpdf::{BuiltinFont, Color, Mm, PdfDocument, Rect, Rgb};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Seguridad de la conexion con el servidor SMTP
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Sin cifrado (solo para servidores de la red local)
+    None,
+    /// Conexion normal que se cifra con STARTTLS (puerto 587)
+    StartTls,
+    /// Conexion cifrada desde el inicio (puerto 465)
+    Tls,
+}
+
+/// Configuracion del servidor SMTP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: Option<u16>,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Remitente (ej: `Direccion <direccion@escuela.mx>`)
+    pub from: String,
+}
+
+/// Resultado del envio de horarios
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmailReport {
+    /// Correos enviados (en modo de prueba no se envia ninguno)
+    pub sent: Vec<String>,
+    /// Profesores que no recibieron correo y el por que
+    pub skipped: Vec<String>,
+    /// Archivos `.eml` escritos en modo de prueba
+    pub files: Vec<String>,
+}
+
+/// Clase del horario de un profesor
+#[derive(Debug, FromRow)]
+struct MailRow {
+    combined_id: Option<i16>,
+    day: String,
+    module_index: i16,
+    week: String,
+    subject: String,
+    color: String,
+    group_name: String,
+    subgroup: Option<String>,
+    room: Option<String>,
+}
+
+/// Clase como se muestra en la celda (las clases compartidas juntan sus grupos)
+struct MailClass {
+    subject: String,
+    color: String,
+    details: String,
+}
+
+/// Funcion para enviar a cada profesor su horario por correo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `settings` - Servidor SMTP y remitente
+/// * `subject` - Asunto del correo
+/// * `teacher_ids` - Profesores a los que se envia, si es nulo se envia a todos
+/// * `dry_run_directory` - Si se indica, los correos se escriben como `.eml` en esta carpeta
+///   en lugar de enviarse
+/// El cuerpo del correo es el horario en HTML y lleva el horario en PDF y, si el calendario
+/// escolar esta definido, el archivo `.ics` para importarlo en su calendario.
+/// Se omiten los profesores sin correo o sin clases
+/// Retorna los correos enviados, los profesores omitidos y los archivos escritos
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn email_teacher_timetables(
+    pool: tauri::State<'_, AppState>,
+    settings: SmtpSettings,
+    subject: String,
+    teacher_ids: Option<Vec<i16>>,
+    dry_run_directory: Option<String>,
+) -> Result<EmailReport, String> {
+    let (messages, skipped) =
+        compose_teacher_emails(&pool.db, &settings.from, &subject, teacher_ids).await?;
+
+    let mut report = EmailReport {
+        skipped,
+        ..Default::default()
+    };
+
+    // Modo de prueba: los correos se guardan en disco
+    if let Some(directory) = dry_run_directory {
+        std::fs::create_dir_all(&directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory, e))?;
+        for (file_name, _, message) in messages {
+            let path = Path::new(&directory).join(format!("{}.eml", file_name));
+            std::fs::write(&path, message.formatted())
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            report.files.push(path.to_string_lossy().to_string());
+        }
+        return Ok(report);
+    }
+
+    let transport = smtp_transport(&settings)?;
+    for (_, to, message) in messages {
+        match transport.send(message).await {
+            Ok(_) => report.sent.push(to),
+            Err(e) => report
+                .skipped
+                .push(format!("{}: failed to send: {}", to, e)),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Arma el correo de cada profesor como (nombre de archivo, destinatario, mensaje)
+/// y la lista de profesores omitidos
+pub async fn compose_teacher_emails(
+    pool: &DbPool,
+    from: &str,
+    subject: &str,
+    teacher_ids: Option<Vec<i16>>,
+) -> Result<(Vec<(String, String, Message)>, Vec<String>), String> {
+    let from: Mailbox = from
+        .parse()
+        .map_err(|e| format!("Invalid sender '{}': {}", from, e))?;
+    let calendar = fetch_school_calendar(pool).await?;
+
+    let teachers: Vec<(i16, String, String, Option<String>)> = sqlx::query_as(
+        "SELECT id, name, father_lastname, email FROM teachers ORDER BY father_lastname, name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get teachers: {}", e))?;
+
+    let mut messages: Vec<(String, String, Message)> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    for (id, name, father_lastname, email) in teachers {
+        if teacher_ids.as_ref().is_some_and(|ids| !ids.contains(&id)) {
+            continue;
+        }
+        let full_name = format!("{} {}", name, father_lastname);
+
+        let Some(email) = email.filter(|e| !e.trim().is_empty()) else {
+            skipped.push(format!("{}: no email", full_name));
+            continue;
+        };
+        let to: Mailbox = match format!("{} <{}>", full_name, email.trim()).parse() {
+            Ok(to) => to,
+            Err(e) => {
+                skipped.push(format!("{}: invalid email '{}': {}", full_name, email, e));
+                continue;
+            }
+        };
+
+        let cells = teacher_classes(pool, id).await?;
+        if cells.is_empty() {
+            skipped.push(format!("{}: no classes", full_name));
+            continue;
+        }
+        let hours = module_labels(calendar.as_ref());
+
+        let mut parts = MultiPart::mixed()
+            .singlepart(SinglePart::html(email_body(&full_name, &cells, &hours)))
+            .singlepart(Attachment::new("horario.pdf".to_string()).body(
+                timetable_pdf(&full_name, &cells, &hours)?,
+                ContentType::parse("application/pdf").unwrap(),
+            ));
+        if let Some(calendar) = &calendar {
+            let ics = build_ical(pool, calendar, IcalTarget::Teacher, id).await?;
+            parts = parts.singlepart(Attachment::new("horario.ics".to_string()).body(
+                ics,
+                ContentType::parse("text/calendar; charset=utf-8").unwrap(),
+            ));
+        }
+
+        let message = Message::builder()
+            .from(from.clone())
+            .to(to.clone())
+            .subject(subject)
+            .multipart(parts)
+            .map_err(|e| format!("Failed to build email for {}: {}", full_name, e))?;
+
+        let file_name: String = format!("{}_{}_{}", id, father_lastname, name)
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        messages.push((file_name, to.to_string(), message));
+    }
+
+    Ok((messages, skipped))
+}
+
+/// Conexion con el servidor SMTP segun la configuracion
+fn smtp_transport(settings: &SmtpSettings) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let host = settings.host.trim();
+    let mut builder = match settings.security {
+        SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| format!("Invalid SMTP server '{}': {}", host, e))?,
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+            .map_err(|e| format!("Invalid SMTP server '{}': {}", host, e))?,
+    };
+    if let Some(port) = settings.port {
+        builder = builder.port(port);
+    }
+    if let Some(username) = settings.username.as_ref().filter(|u| !u.is_empty()) {
+        builder = builder.credentials(Credentials::new(
+            username.clone(),
+            settings.password.clone().unwrap_or_default(),
+        ));
+    }
+    Ok(builder.build())
+}
+
+/// Clases del profesor por (dia, modulo)
+async fn teacher_classes(
+    pool: &DbPool,
+    teacher_id: i16,
+) -> Result<BTreeMap<(usize, i16), Vec<MailClass>>, String> {
+    let rows: Vec<MailRow> = sqlx::query_as::<_, MailRow>(
+        "
+        SELECT a.combined_id, a.day, a.module_index, a.week,
+               s.name AS subject, s.color,
+               g.grade || g.\"group\" AS group_name,
+               sg.name AS subgroup,
+               c.building_id || c.building_number AS room
+        FROM assignments a
+        JOIN subjects s ON s.id = a.subject_id
+        JOIN groups g ON g.id = a.group_id
+        LEFT JOIN subgroups sg ON sg.id = a.subgroup_id
+        LEFT JOIN classroom c ON c.id = a.classroom_id
+        WHERE a.teacher_id = ?1
+        ORDER BY a.week, g.grade, g.\"group\"
+        ",
+    )
+    .bind(teacher_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get assignments: {}", e))?;
+
+    Ok(mail_cells(rows))
+}
+
+/// Arma las celdas del horario, una clase compartida se muestra una vez con todos sus grupos
+fn mail_cells(rows: Vec<MailRow>) -> BTreeMap<(usize, i16), Vec<MailClass>> {
+    let mut classes: BTreeMap<(usize, i16, i32), (MailRow, Vec<String>)> = BTreeMap::new();
+    for (position, row) in rows.into_iter().enumerate() {
+        let Some(day) = SCHOOL_DAYS.iter().position(|d| *d == row.day) else {
+            continue;
+        };
+        let key = match row.combined_id {
+            Some(combined_id) => -(combined_id as i32),
+            None => position as i32,
+        };
+        let mut group_name = row.group_name.clone();
+        if let Some(subgroup) = &row.subgroup {
+            group_name.push_str(&format!(" ({})", subgroup));
+        }
+        classes
+            .entry((day, row.module_index, key))
+            .or_insert_with(|| (row, Vec::new()))
+            .1
+            .push(group_name);
+    }
+
+    let mut cells: BTreeMap<(usize, i16), Vec<MailClass>> = BTreeMap::new();
+    for ((day, module_index, _), (row, groups)) in classes {
+        let mut subject = row.subject;
+        if row.week != "all" {
+            subject.push_str(&format!(" [{}]", row.week.to_uppercase()));
+        }
+        let mut details = groups.join(", ");
+        if let Some(room) = row.room {
+            details.push_str(&format!(" - {}", room));
+        }
+        cells
+            .entry((day, module_index))
+            .or_default()
+            .push(MailClass {
+                subject,
+                color: row.color,
+                details,
+            });
+    }
+
+    cells
+}
+
+/// Cuerpo HTML del correo, con estilos en linea porque los clientes de correo ignoran `<style>`
+fn email_body(
+    name: &str,
+    cells: &BTreeMap<(usize, i16), Vec<MailClass>>,
+    hours: &[String],
+) -> String {
+    let cell_style = "border: 1px solid #ccc; padding: 4px; vertical-align: top; font-size: 12px;";
+    let mut html = format!(
+        "<div style=\"font-family: sans-serif;\"><p>Hola {},</p><p>Este es tu horario:</p>\n<table style=\"border-collapse: collapse;\">\n<tr><th style=\"{}\"></th>",
+        escape_html(name),
+        cell_style
+    );
+    for day in SCHOOL_DAYS {
+        html.push_str(&format!("<th style=\"{}\">{}</th>", cell_style, day));
+    }
+    html.push_str("</tr>\n");
+
+    for (module_index, hour) in hours.iter().enumerate() {
+        html.push_str(&format!(
+            "<tr><td style=\"{} font-weight: bold;\">{}</td>",
+            cell_style,
+            escape_html(hour)
+        ));
+        for day in 0..SCHOOL_DAYS.len() {
+            html.push_str(&format!("<td style=\"{}\">", cell_style));
+            for class in cells.get(&(day, module_index as i16)).into_iter().flatten() {
+                html.push_str(&format!(
+                    "<div style=\"background: {}; color: {}; padding: 2px 4px; margin-bottom: 2px; border-radius: 3px;\"><b>{}</b><br>{}</div>",
+                    escape_html(&class.color),
+                    text_color(&class.color),
+                    escape_html(&class.subject),
+                    escape_html(&class.details)
+                ));
+            }
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n<p>Se adjunta el horario en PDF.</p></div>\n");
+    html
+}
+
+/// Horario en PDF (A4 horizontal), una tabla de dias por modulos
+fn timetable_pdf(
+    name: &str,
+    cells: &BTreeMap<(usize, i16), Vec<MailClass>>,
+    hours: &[String],
+) -> Result<Vec<u8>, String> {
+    let (document, page, layer) = PdfDocument::new(name, Mm(297.0), Mm(210.0), "Horario");
+    let font = document
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| format!("Failed to load PDF font: {}", e))?;
+    let bold = document
+        .add_builtin_font(BuiltinFont::HelveticaBold)
+        .map_err(|e| format!("Failed to load PDF font: {}", e))?;
+    let layer = document.get_page(page).get_layer(layer);
+
+    let (left, top, bottom) = (10.0, 188.0, 10.0);
+    let hour_width = 28.0;
+    let day_width = (297.0 - 2.0 * left - hour_width) / SCHOOL_DAYS.len() as f32;
+    let header_height = 8.0;
+    let row_height = (top - bottom - header_height) / hours.len() as f32;
+    let black = Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None));
+
+    layer.use_text(name, 14.0, Mm(left), Mm(195.0), &bold);
+
+    let outline = |x: f32, y: f32, width: f32, height: f32| {
+        layer.set_outline_color(black.clone());
+        layer.set_outline_thickness(0.5);
+        layer.add_rect(
+            Rect::new(Mm(x), Mm(y), Mm(x + width), Mm(y + height)).with_mode(PaintMode::Stroke),
+        );
+    };
+
+    // Encabezado con los dias
+    for (day, day_name) in SCHOOL_DAYS.iter().enumerate() {
+        let x = left + hour_width + day as f32 * day_width;
+        outline(x, top - header_height, day_width, header_height);
+        layer.use_text(*day_name, 9.0, Mm(x + 2.0), Mm(top - 5.5), &bold);
+    }
+
+    for (module_index, hour) in hours.iter().enumerate() {
+        let y = top - header_height - (module_index as f32 + 1.0) * row_height;
+        outline(left, y, hour_width, row_height);
+        layer.use_text(
+            hour.as_str(),
+            8.0,
+            Mm(left + 2.0),
+            Mm(y + row_height - 5.0),
+            &bold,
+        );
+
+        for day in 0..SCHOOL_DAYS.len() {
+            let x = left + hour_width + day as f32 * day_width;
+            let classes = cells.get(&(day, module_index as i16));
+
+            // Si hay varias clases en el modulo (semanas A/B o subgrupos) se reparte la celda
+            let count = classes.map(|c| c.len()).unwrap_or(0).max(1);
+            let height = row_height / count as f32;
+            for (i, class) in classes.into_iter().flatten().enumerate() {
+                let class_y = y + row_height - (i as f32 + 1.0) * height;
+                let (r, g, b) = hex_rgb(&class.color).unwrap_or((255, 255, 255));
+                layer.set_fill_color(Color::Rgb(Rgb::new(
+                    r as f32 / 255.0,
+                    g as f32 / 255.0,
+                    b as f32 / 255.0,
+                    None,
+                )));
+                layer.add_rect(Rect::new(
+                    Mm(x),
+                    Mm(class_y),
+                    Mm(x + day_width),
+                    Mm(class_y + height),
+                ));
+
+                let shade = if text_color(&class.color) == "#fff" {
+                    1.0
+                } else {
+                    0.0
+                };
+                layer.set_fill_color(Color::Rgb(Rgb::new(shade, shade, shade, None)));
+                layer.use_text(
+                    shorten(&class.subject, 34),
+                    7.5,
+                    Mm(x + 1.5),
+                    Mm(class_y + height - 3.5),
+                    &bold,
+                );
+                if height >= 8.0 {
+                    layer.use_text(
+                        shorten(&class.details, 38),
+                        6.5,
+                        Mm(x + 1.5),
+                        Mm(class_y + height - 7.0),
+                        &font,
+                    );
+                }
+            }
+            layer.set_fill_color(black.clone());
+            outline(x, y, day_width, row_height);
+        }
+    }
+
+    document
+        .save_to_bytes()
+        .map_err(|e| format!("Failed to build PDF: {}", e))
+}
+
+/// Recorta un texto para que quepa en la celda del PDF
+fn shorten(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        format!("{}...", text.chars().take(max - 3).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(combined_id: Option<i16>, day: &str, group_name: &str, week: &str) -> MailRow {
+        MailRow {
+            combined_id,
+            day: day.to_string(),
+            module_index: 0,
+            week: week.to_string(),
+            subject: "Fisica".to_string(),
+            color: "#ffffff".to_string(),
+            group_name: group_name.to_string(),
+            subgroup: None,
+            room: Some("A101".to_string()),
+        }
+    }
+
+    #[test]
+    fn mail_cells_merge_combined_classes() {
+        let mut lab = row(None, "Lunes", "1B", "a");
+        lab.subgroup = Some("Lab".to_string());
+        lab.room = None;
+        let rows = vec![
+            row(Some(3), "Lunes", "1A", "all"),
+            row(Some(3), "Lunes", "1C", "all"),
+            lab,
+            row(None, "Sabado", "1A", "all"),
+        ];
+
+        let cells = mail_cells(rows);
+        assert_eq!(cells.len(), 1);
+        let classes = &cells[&(0, 0)];
+        let shown: Vec<(&str, &str)> = classes
+            .iter()
+            .map(|c| (c.subject.as_str(), c.details.as_str()))
+            .collect();
+        assert_eq!(
+            shown,
+            vec![("Fisica", "1A, 1C - A101"), ("Fisica [A]", "1B (Lab)")]
+        );
+    }
+
+    #[test]
+    fn email_body_escapes_names_and_fills_cells() {
+        let mut cells = BTreeMap::new();
+        cells.insert(
+            (1, 0),
+            vec![MailClass {
+                subject: "Fisica <I>".to_string(),
+                color: "#000000".to_string(),
+                details: "1A - A101".to_string(),
+            }],
+        );
+        let hours = vec!["07:00 - 08:00".to_string()];
+
+        let html = email_body("Ana & Luis", &cells, &hours);
+        assert!(html.contains("Hola Ana &amp; Luis,"));
+        assert!(html.contains("<b>Fisica &lt;I&gt;</b><br>1A - A101"));
+        assert!(html.contains("color: #fff;"));
+        // Una fila de encabezado y una por modulo
+        assert_eq!(html.matches("<tr>").count(), 2);
+        // La clase va en la columna del martes
+        let row = html.split("<tr>").nth(2).unwrap();
+        let columns: Vec<&str> = row.split("<td").collect();
+        assert!(columns[3].contains("Fisica"));
+        assert!(!columns[2].contains("Fisica"));
+    }
+
+    #[test]
+    fn shorten_cuts_long_texts_by_characters() {
+        assert_eq!(shorten("Fisica", 10), "Fisica");
+        assert_eq!(shorten("Matematicas", 8), "Matem...");
+        assert_eq!(shorten("Educación física", 8), "Educa...");
+    }
+}
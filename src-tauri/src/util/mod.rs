@@ -1,2 +1,13 @@
 pub mod assignments;
+pub mod backup;
+pub mod calendar;
+pub mod conflicts;
+pub mod fet;
+pub mod ical;
+pub mod mail;
+pub mod search;
+pub mod server;
+pub mod site;
+pub mod slots;
+pub mod snapshots;
 pub mod xlsx;
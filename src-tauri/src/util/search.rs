@@ -0,0 +1,110 @@
+use crate::db::{AppState, DbPool};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+/// Resultados que se regresan si no se indica un limite
+const DEFAULT_LIMIT: i64 = 50;
+
+/// Resultado de la busqueda
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SearchResult {
+    /// Tipo de dato: `teacher`, `subject`, `group` o `classroom`
+    pub kind: String,
+    pub id: i16,
+    /// Texto principal (nombre del profesor, materia, grupo o salon)
+    pub title: String,
+    /// Texto secundario (correo, abreviatura y especialidad, carrera o tipo de salon)
+    pub detail: String,
+}
+
+/// Funcion para buscar profesores, materias, grupos y salones
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `query` - Texto a buscar, cada palabra puede ser el inicio de una palabra (ej: "mat gonz")
+/// * `limit` - Cantidad maxima de resultados (50 por defecto)
+/// No distingue mayusculas ni acentos ("jose" encuentra "José")
+/// Retorna los resultados ordenados por relevancia
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn search(
+    pool: tauri::State<'_, AppState>,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, String> {
+    search_entities(&pool.db, &query, limit.unwrap_or(DEFAULT_LIMIT)).await
+}
+
+/// Busca en el indice de texto completo (`search_index`)
+pub async fn search_entities(
+    pool: &DbPool,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<SearchResult>, String> {
+    let Some(query) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+
+    // El nombre pesa mas que el texto secundario
+    sqlx::query_as::<_, SearchResult>(
+        "
+        SELECT kind, entity_id AS id, title, content AS detail
+        FROM search_index
+        WHERE search_index MATCH ?1
+        ORDER BY bm25(search_index, 0.0, 0.0, 10.0, 1.0), title
+        LIMIT ?2
+        ",
+    )
+    .bind(query)
+    .bind(limit.max(1))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to search: {}", e))
+}
+
+/// Convierte el texto del usuario en una consulta de FTS5 donde cada palabra es un prefijo
+/// (ej: `mate gonz` -> `"mate"* "gonz"*`). Se quitan los simbolos para que no se
+/// interpreten como operadores de FTS5
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fts_query_makes_every_word_a_prefix() {
+        assert_eq!(
+            fts_query("mate gonz").as_deref(),
+            Some("\"mate\"* \"gonz\"*")
+        );
+        assert_eq!(fts_query("  Química  ").as_deref(), Some("\"Química\"*"));
+        assert_eq!(fts_query("1A").as_deref(), Some("\"1A\"*"));
+    }
+
+    #[test]
+    fn fts_query_removes_fts_operators() {
+        // Las comillas, guiones, asteriscos y parentesis separan palabras
+        assert_eq!(
+            fts_query("\"mate\" -gonz* (NEAR)").as_deref(),
+            Some("\"mate\"* \"gonz\"* \"NEAR\"*")
+        );
+        assert_eq!(fts_query("a:b").as_deref(), Some("\"a\"* \"b\"*"));
+    }
+
+    #[test]
+    fn fts_query_is_empty_without_words() {
+        assert_eq!(fts_query(""), None);
+        assert_eq!(fts_query("  *-\" ()"), None);
+    }
+}
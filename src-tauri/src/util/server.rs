@@ -0,0 +1,411 @@
+use crate::class::classrooms::Classroom;
+use crate::class::groups::Group;
+use crate::db::{AppState, DbPool};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex};
+
+/// Tamaño maximo de la peticion (linea inicial y headers)
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Tiempo maximo para recibir la peticion
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Estado del servidor HTTP, se registra con `manage()` junto al `AppState`
+#[derive(Default)]
+pub struct ApiServerState {
+    running: Mutex<Option<RunningServer>>,
+}
+
+/// Servidor en ejecucion
+struct RunningServer {
+    address: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// Profesor como se publica en la API (sin datos de contacto)
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ApiTeacher {
+    pub id: i16,
+    pub name: String,
+    pub father_lastname: String,
+    pub mother_lastname: Option<String>,
+}
+
+/// Clase de un horario con los nombres para mostrar
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ApiClass {
+    pub day: String,
+    pub module_index: i16,
+    pub week: String,
+    pub subject_id: i16,
+    pub subject: String,
+    pub color: String,
+    pub teacher_id: i16,
+    pub teacher: String,
+    pub group_id: i16,
+    pub group_name: String,
+    pub subgroup: Option<String>,
+    pub classroom_id: Option<i16>,
+    pub room: Option<String>,
+    pub combined_id: Option<i16>,
+}
+
+/// Funcion para iniciar el servidor HTTP de solo lectura
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `server` - Estado del servidor
+/// * `address` - Direccion y puerto (ej: `127.0.0.1:8787`, o la IP del equipo en la red local
+///   como `192.168.1.10:8787`). Solo se permiten direcciones locales o de redes privadas
+/// * `token` - Token de acceso, se manda como `Authorization: Bearer <token>` o `?token=<token>`
+/// Rutas (solo GET, responden JSON):
+/// * `/api/groups`, `/api/teachers`, `/api/classrooms`
+/// * `/api/groups/{id}/timetable`, `/api/teachers/{id}/timetable`, `/api/classrooms/{id}/timetable`
+/// Retorna la direccion en la que quedo escuchando el servidor
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn start_api_server(
+    pool: tauri::State<'_, AppState>,
+    server: tauri::State<'_, ApiServerState>,
+    address: String,
+    token: String,
+) -> Result<String, String> {
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err("An access token is required".to_string());
+    }
+    let address: SocketAddr = address
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid address '{}': {}", address, e))?;
+    if !is_private_address(address.ip()) {
+        return Err(format!(
+            "The API server can only listen on a loopback or private network address, not {}",
+            address.ip()
+        ));
+    }
+
+    let mut running = server.running.lock().await;
+    if let Some(current) = running.as_ref() {
+        return Err(format!(
+            "The API server is already running on {}",
+            current.address
+        ));
+    }
+
+    let listener = TcpListener::bind(address)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", address, e))?;
+    let local_address = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to get server address: {}", e))?
+        .to_string();
+
+    let (shutdown, mut stop) = oneshot::channel::<()>();
+    let db = pool.db.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut stop => break,
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        tokio::spawn(handle_connection(db.clone(), token.clone(), stream));
+                    }
+                }
+            }
+        }
+    });
+
+    *running = Some(RunningServer {
+        address: local_address.clone(),
+        shutdown,
+    });
+
+    Ok(local_address)
+}
+
+/// Funcion para detener el servidor HTTP
+/// # Argumentos
+/// * `server` - Estado del servidor
+/// Retorna `true` si el servidor estaba corriendo
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn stop_api_server(server: tauri::State<'_, ApiServerState>) -> Result<bool, String> {
+    match server.running.lock().await.take() {
+        Some(running) => {
+            let _ = running.shutdown.send(());
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Funcion para saber si el servidor HTTP esta corriendo
+/// # Argumentos
+/// * `server` - Estado del servidor
+/// Retorna la direccion del servidor o nada si esta detenido
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_api_server_status(
+    server: tauri::State<'_, ApiServerState>,
+) -> Result<Option<String>, String> {
+    Ok(server
+        .running
+        .lock()
+        .await
+        .as_ref()
+        .map(|running| running.address.clone()))
+}
+
+/// Atiende una conexion: lee una peticion, responde y cierra
+async fn handle_connection(pool: DbPool, token: String, mut stream: TcpStream) {
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Some(request)) => request,
+        _ => return,
+    };
+
+    let mut lines = request.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or_default();
+    let authorization = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .map(|(_, value)| value.trim());
+
+    let (status, body) = handle_request(&pool, &token, method, target, authorization).await;
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nAccess-Control-Allow-Headers: Authorization\r\nAccess-Control-Allow-Methods: GET, OPTIONS\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Lee la linea inicial y los headers de la peticion (el cuerpo se ignora)
+async fn read_request(stream: &mut TcpStream) -> Option<String> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 || buffer.len() + read > MAX_REQUEST_SIZE {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    String::from_utf8(buffer).ok()
+}
+
+/// Resuelve una peticion y retorna (codigo HTTP, cuerpo JSON)
+pub async fn handle_request(
+    pool: &DbPool,
+    token: &str,
+    method: &str,
+    target: &str,
+    authorization: Option<&str>,
+) -> (u16, String) {
+    // Respuesta al preflight de CORS, no lleva token
+    if method == "OPTIONS" {
+        return (204, String::new());
+    }
+    if method != "GET" {
+        return error_response(405, "Only GET requests are allowed");
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query_token = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == "token")
+        .and_then(|(_, value)| percent_decode(value));
+    let bearer = authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let authorized = bearer
+        .or(query_token)
+        .is_some_and(|given| tokens_match(given.as_bytes(), token.as_bytes()));
+    if !authorized {
+        return error_response(401, "Invalid or missing access token");
+    }
+
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let result = match segments.as_slice() {
+        ["api", "groups"] => sqlx::query_as::<_, Group>("SELECT * FROM groups ORDER BY grade, \"group\"")
+            .fetch_all(pool)
+            .await
+            .map(|rows| serde_json::to_string(&rows)),
+        ["api", "teachers"] => sqlx::query_as::<_, ApiTeacher>(
+            "SELECT id, name, father_lastname, mother_lastname FROM teachers ORDER BY father_lastname, name",
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| serde_json::to_string(&rows)),
+        ["api", "classrooms"] => sqlx::query_as::<_, Classroom>(
+            "SELECT * FROM classroom ORDER BY building_id, building_number",
+        )
+        .fetch_all(pool)
+        .await
+        .map(|rows| serde_json::to_string(&rows)),
+        ["api", entity, id, "timetable"] => {
+            let column = match *entity {
+                "groups" => "a.group_id",
+                "teachers" => "a.teacher_id",
+                "classrooms" => "a.classroom_id",
+                _ => return error_response(404, "Not found"),
+            };
+            let Ok(id) = id.parse::<i16>() else {
+                return error_response(400, "Invalid ID");
+            };
+            timetable(pool, column, id)
+                .await
+                .map(|rows| serde_json::to_string(&rows))
+        }
+        _ => return error_response(404, "Not found"),
+    };
+
+    match result {
+        Ok(Ok(body)) => (200, body),
+        Ok(Err(e)) => error_response(500, &format!("Failed to serialize response: {}", e)),
+        Err(e) => error_response(500, &format!("Failed to query database: {}", e)),
+    }
+}
+
+/// Clases del horario de un grupo, profesor o salon
+async fn timetable(pool: &DbPool, column: &str, id: i16) -> Result<Vec<ApiClass>, sqlx::Error> {
+    sqlx::query_as::<_, ApiClass>(&format!(
+        "
+        SELECT a.day, a.module_index, a.week,
+               a.subject_id, s.name AS subject, s.color,
+               a.teacher_id, t.name || ' ' || t.father_lastname AS teacher,
+               a.group_id, g.grade || g.\"group\" AS group_name,
+               sg.name AS subgroup,
+               a.classroom_id, c.building_id || c.building_number AS room,
+               a.combined_id
+        FROM assignments a
+        JOIN subjects s ON s.id = a.subject_id
+        JOIN teachers t ON t.id = a.teacher_id
+        JOIN groups g ON g.id = a.group_id
+        LEFT JOIN subgroups sg ON sg.id = a.subgroup_id
+        LEFT JOIN classroom c ON c.id = a.classroom_id
+        WHERE {} = ?1
+        ORDER BY a.day, a.module_index, a.week
+        ",
+        column
+    ))
+    .bind(id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Checa si la direccion es local (loopback) o de una red privada
+/// No se permite `0.0.0.0` porque tambien escucharia en las interfaces publicas
+fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        // fc00::/7 son las direcciones locales unicas de IPv6
+        IpAddr::V6(ip) => ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+/// Compara el token sin cortar en el primer byte distinto
+/// El tiempo solo depende del largo del token esperado
+fn tokens_match(given: &[u8], expected: &[u8]) -> bool {
+    let mut diff = given.len() ^ expected.len();
+    for (i, byte) in expected.iter().enumerate() {
+        diff |= (byte ^ given.get(i).copied().unwrap_or(0)) as usize;
+    }
+    diff == 0
+}
+
+/// Decodifica un valor de la query (ej: `a%2Bb` -> `a+b`, `+` es un espacio)
+/// Retorna nada si tiene un escape invalido
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Respuesta de error en JSON (ej: {"error": "Not found"})
+fn error_response(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_local_and_private_addresses_are_allowed() {
+        for address in [
+            "127.0.0.1",
+            "192.168.1.10",
+            "10.0.0.5",
+            "172.16.3.1",
+            "::1",
+            "fd00::1",
+        ] {
+            assert!(is_private_address(address.parse().unwrap()), "{}", address);
+        }
+        for address in ["0.0.0.0", "8.8.8.8", "::", "2001:db8::1"] {
+            assert!(!is_private_address(address.parse().unwrap()), "{}", address);
+        }
+    }
+
+    #[test]
+    fn tokens_match_needs_the_same_bytes() {
+        assert!(tokens_match(b"secreto", b"secreto"));
+        assert!(!tokens_match(b"secret", b"secreto"));
+        assert!(!tokens_match(b"secretoo", b"secreto"));
+        assert!(!tokens_match(b"Secreto", b"secreto"));
+        assert!(!tokens_match(b"", b"secreto"));
+    }
+
+    #[test]
+    fn percent_decode_decodes_query_values() {
+        assert_eq!(percent_decode("a%2Bb%3D%3D").as_deref(), Some("a+b=="));
+        assert_eq!(percent_decode("mi+token").as_deref(), Some("mi token"));
+        assert_eq!(percent_decode("%C3%B1").as_deref(), Some("ñ"));
+        assert_eq!(percent_decode("abc").as_deref(), Some("abc"));
+        // Escapes incompletos o invalidos
+        assert_eq!(percent_decode("abc%2"), None);
+        assert_eq!(percent_decode("abc%zz"), None);
+    }
+}
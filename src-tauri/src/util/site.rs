@@ -0,0 +1,393 @@
+use crate::class::school_calendar::{fetch_school_calendar, SchoolCalendar};
+use crate::db::{AppState, DbPool};
+use crate::util::calendar::{MODULES_PER_DAY, SCHOOL_DAYS};
+use chrono::Utc;
+use sqlx::prelude::FromRow;
+use std::path::Path;
+
+/// Hoja de estilos compartida por todas las paginas
+const SITE_CSS: &str = "body { font-family: sans-serif; margin: 2rem; color: #222; }
+h1 { font-size: 1.5rem; }
+a { color: #1d4ed8; }
+nav a { margin-right: 1rem; }
+ul { columns: 3; }
+table { border-collapse: collapse; width: 100%; table-layout: fixed; }
+th, td { border: 1px solid #ccc; padding: 0.25rem; vertical-align: top; font-size: 0.85rem; }
+th { background: #f3f4f6; }
+td.hour { width: 7rem; background: #f3f4f6; font-weight: bold; }
+.class { border-radius: 4px; padding: 0.25rem; margin-bottom: 0.25rem; }
+.class a { color: inherit; }
+.subject { font-weight: bold; }
+footer { margin-top: 2rem; font-size: 0.75rem; color: #666; }
+";
+
+/// Tipo de pagina del sitio, cada una vive en su propia carpeta
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SitePage {
+    Group,
+    Teacher,
+    Classroom,
+}
+
+impl SitePage {
+    fn directory(&self) -> &'static str {
+        match self {
+            SitePage::Group => "groups",
+            SitePage::Teacher => "teachers",
+            SitePage::Classroom => "classrooms",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            SitePage::Group => "Grupos",
+            SitePage::Teacher => "Profesores",
+            SitePage::Classroom => "Salones",
+        }
+    }
+}
+
+/// Clase del horario con los nombres que se muestran en las paginas
+#[derive(Debug, FromRow)]
+struct SiteRow {
+    combined_id: Option<i16>,
+    day: String,
+    module_index: i16,
+    week: String,
+    group_id: i16,
+    group_name: String,
+    subgroup: Option<String>,
+    subject: String,
+    color: String,
+    teacher_id: i16,
+    teacher: String,
+    classroom_id: Option<i16>,
+    room: Option<String>,
+}
+
+/// Funcion para generar el sitio estatico con todos los horarios
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `directory` - Carpeta donde se crea el sitio
+/// Crea `index.html`, `style.css` y una pagina por grupo (`groups/`), profesor (`teachers/`)
+/// y salon (`classrooms/`). Los enlaces son relativos, la carpeta se puede copiar a
+/// cualquier servidor web
+/// Retorna las rutas de los archivos creados
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn export_html_site(
+    pool: tauri::State<'_, AppState>,
+    directory: String,
+) -> Result<Vec<String>, String> {
+    let pages = build_site(&pool.db).await?;
+
+    let mut files: Vec<String> = Vec::new();
+    for (relative_path, content) in pages {
+        let path = Path::new(&directory).join(&relative_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        files.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(files)
+}
+
+/// Arma los archivos del sitio como (ruta relativa, contenido)
+pub async fn build_site(pool: &DbPool) -> Result<Vec<(String, String)>, String> {
+    let calendar = fetch_school_calendar(pool).await?;
+    let hours = module_labels(calendar.as_ref());
+
+    let rows: Vec<SiteRow> = sqlx::query_as::<_, SiteRow>(
+        "
+        SELECT a.combined_id, a.day, a.module_index, a.week,
+               a.group_id, g.grade || g.\"group\" AS group_name,
+               sg.name AS subgroup,
+               s.name AS subject, s.color,
+               a.teacher_id, t.name || ' ' || t.father_lastname AS teacher,
+               a.classroom_id, c.building_id || c.building_number AS room
+        FROM assignments a
+        JOIN subjects s ON s.id = a.subject_id
+        JOIN teachers t ON t.id = a.teacher_id
+        JOIN groups g ON g.id = a.group_id
+        LEFT JOIN subgroups sg ON sg.id = a.subgroup_id
+        LEFT JOIN classroom c ON c.id = a.classroom_id
+        ORDER BY a.day, a.module_index, a.week, g.grade, g.\"group\"
+        ",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get assignments: {}", e))?;
+
+    let groups: Vec<(i16, String)> =
+        sqlx::query_as("SELECT id, grade || \"group\" FROM groups ORDER BY grade, \"group\"")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to get groups: {}", e))?;
+    let teachers: Vec<(i16, String)> = sqlx::query_as(
+        "SELECT id, name || ' ' || father_lastname FROM teachers ORDER BY father_lastname, name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get teachers: {}", e))?;
+    let classrooms: Vec<(i16, String)> = sqlx::query_as(
+        "SELECT id, building_id || building_number FROM classroom ORDER BY building_id, building_number",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to get classrooms: {}", e))?;
+
+    let mut files: Vec<(String, String)> = vec![("style.css".to_string(), SITE_CSS.to_string())];
+
+    let lists = [
+        (SitePage::Group, &groups),
+        (SitePage::Teacher, &teachers),
+        (SitePage::Classroom, &classrooms),
+    ];
+
+    // Indice con todos los horarios
+    let mut index = String::from("<h1>Horarios</h1>\n");
+    for (page, entries) in &lists {
+        index.push_str(&format!("<h2>{}</h2>\n<ul>\n", page.title()));
+        for (id, name) in entries.iter() {
+            index.push_str(&format!(
+                "<li><a href=\"{}/{}.html\">{}</a></li>\n",
+                page.directory(),
+                id,
+                escape_html(name)
+            ));
+        }
+        index.push_str("</ul>\n");
+    }
+    files.push(("index.html".to_string(), html_page("Horarios", "", &index)));
+
+    for (page, entries) in lists {
+        for (id, name) in entries.iter() {
+            let page_rows: Vec<&SiteRow> = rows
+                .iter()
+                .filter(|row| match page {
+                    SitePage::Group => row.group_id == *id,
+                    SitePage::Teacher => row.teacher_id == *id,
+                    SitePage::Classroom => row.classroom_id == Some(*id),
+                })
+                .collect();
+            let body = format!(
+                "<nav><a href=\"../index.html\">Inicio</a></nav>\n<h1>{}</h1>\n{}",
+                escape_html(name),
+                timetable(page, &page_rows, &hours)
+            );
+            files.push((
+                format!("{}/{}.html", page.directory(), id),
+                html_page(name, "../", &body),
+            ));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Tabla de dias por modulos con las clases de una pagina
+fn timetable(page: SitePage, rows: &[&SiteRow], hours: &[String]) -> String {
+    let mut html = String::from("<table>\n<tr><th></th>");
+    for day in SCHOOL_DAYS {
+        html.push_str(&format!("<th>{}</th>", day));
+    }
+    html.push_str("</tr>\n");
+
+    for (module_index, hour) in hours.iter().enumerate() {
+        html.push_str(&format!(
+            "<tr><td class=\"hour\">{}</td>",
+            escape_html(hour)
+        ));
+        for day in SCHOOL_DAYS {
+            let cell: Vec<&&SiteRow> = rows
+                .iter()
+                .filter(|row| row.day == day && row.module_index as usize == module_index)
+                .collect();
+
+            html.push_str("<td>");
+            let mut shown: Vec<i16> = Vec::new();
+            for row in &cell {
+                // En profesores y salones una clase compartida se muestra una vez con todos sus grupos
+                if page != SitePage::Group {
+                    if let Some(combined_id) = row.combined_id {
+                        if shown.contains(&combined_id) {
+                            continue;
+                        }
+                        shown.push(combined_id);
+                    }
+                }
+                let classmates: Vec<&&SiteRow> = match row.combined_id {
+                    Some(combined_id) if page != SitePage::Group => cell
+                        .iter()
+                        .copied()
+                        .filter(|other| other.combined_id == Some(combined_id))
+                        .collect(),
+                    _ => vec![row],
+                };
+                html.push_str(&class_entry(page, row, &classmates));
+            }
+            html.push_str("</td>");
+        }
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n");
+    html
+}
+
+/// Bloque de una clase dentro de la celda, con enlaces a las otras paginas
+fn class_entry(page: SitePage, row: &SiteRow, classmates: &[&&SiteRow]) -> String {
+    let mut subject = escape_html(&row.subject);
+    if let Some(subgroup) = &row.subgroup {
+        subject.push_str(&format!(" ({})", escape_html(subgroup)));
+    }
+    if row.week != "all" {
+        subject.push_str(&format!(" [{}]", row.week.to_uppercase()));
+    }
+
+    let mut details: Vec<String> = Vec::new();
+    if page != SitePage::Group {
+        let groups: Vec<String> = classmates
+            .iter()
+            .map(|other| link(SitePage::Group, other.group_id, &other.group_name))
+            .collect();
+        details.push(groups.join(", "));
+    }
+    if page != SitePage::Teacher {
+        details.push(link(SitePage::Teacher, row.teacher_id, &row.teacher));
+    }
+    if page != SitePage::Classroom {
+        if let (Some(classroom_id), Some(room)) = (row.classroom_id, &row.room) {
+            details.push(link(SitePage::Classroom, classroom_id, room));
+        }
+    }
+
+    format!(
+        "<div class=\"class\" style=\"background: {}; color: {}\"><div class=\"subject\">{}</div>{}</div>",
+        escape_html(&row.color),
+        text_color(&row.color),
+        subject,
+        details
+            .iter()
+            .map(|d| format!("<div>{}</div>", d))
+            .collect::<String>()
+    )
+}
+
+/// Enlace relativo a otra pagina del sitio (desde una subcarpeta)
+fn link(page: SitePage, id: i16, name: &str) -> String {
+    format!(
+        "<a href=\"../{}/{}.html\">{}</a>",
+        page.directory(),
+        id,
+        escape_html(name)
+    )
+}
+
+/// Documento HTML completo con la hoja de estilos compartida
+fn html_page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"es\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{}style.css\">\n</head>\n<body>\n{}<footer>Generado el {}</footer>\n</body>\n</html>\n",
+        escape_html(title),
+        root,
+        body,
+        Utc::now().format("%Y-%m-%d %H:%M UTC")
+    )
+}
+
+/// Nombre de cada modulo, con su horario si el calendario escolar lo tiene
+pub fn module_labels(calendar: Option<&SchoolCalendar>) -> Vec<String> {
+    (0..MODULES_PER_DAY)
+        .map(|m| {
+            calendar
+                .and_then(|c| c.module_time(m))
+                .map(|t| format!("{} - {}", t.start_time, t.end_time))
+                .unwrap_or_else(|| format!("Modulo {}", m + 1))
+        })
+        .collect()
+}
+
+/// Componentes RGB de un color hexadecimal (ej: `#ff8800` o `#f80`)
+pub fn hex_rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().trim_start_matches('#');
+    let hex: String = if hex.len() == 3 {
+        hex.chars().flat_map(|c| [c, c]).collect()
+    } else {
+        hex.to_string()
+    };
+    let channel = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+    };
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Color del texto (negro o blanco) que se lee mejor sobre el color de la materia
+pub fn text_color(background: &str) -> &'static str {
+    let Some((r, g, b)) = hex_rgb(background) else {
+        return "#000";
+    };
+    let luminance = 0.299 * f32::from(r) + 0.587 * f32::from(g) + 0.114 * f32::from(b);
+    if luminance < 140.0 {
+        "#fff"
+    } else {
+        "#000"
+    }
+}
+
+/// Escapa los caracteres especiales de HTML
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class::school_calendar::ModuleTime;
+
+    #[test]
+    fn hex_rgb_reads_long_and_short_colors() {
+        assert_eq!(hex_rgb("#ff8800"), Some((255, 136, 0)));
+        assert_eq!(hex_rgb(" #f80 "), Some((255, 136, 0)));
+        assert_eq!(hex_rgb("1d4ed8"), Some((29, 78, 216)));
+        assert_eq!(hex_rgb("#12345"), None);
+        assert_eq!(hex_rgb("rojo"), None);
+    }
+
+    #[test]
+    fn text_color_contrasts_with_the_background() {
+        assert_eq!(text_color("#000"), "#fff");
+        assert_eq!(text_color("#1d4ed8"), "#fff");
+        assert_eq!(text_color("#ffff00"), "#000");
+        // Los colores invalidos llevan texto negro
+        assert_eq!(text_color("rojo"), "#000");
+    }
+
+    #[test]
+    fn module_labels_use_the_calendar_times() {
+        let calendar = SchoolCalendar {
+            term_start: "2024-08-26".to_string(),
+            term_end: "2024-12-13".to_string(),
+            week_a_start: None,
+            modules: vec![ModuleTime {
+                module_index: 1,
+                start_time: "07:50".to_string(),
+                end_time: "08:40".to_string(),
+            }],
+        };
+
+        let labels = module_labels(Some(&calendar));
+        assert_eq!(labels.len(), MODULES_PER_DAY as usize);
+        assert_eq!(labels[0], "Modulo 1");
+        assert_eq!(labels[1], "07:50 - 08:40");
+        assert_eq!(module_labels(None)[2], "Modulo 3");
+    }
+}
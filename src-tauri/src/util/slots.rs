@@ -0,0 +1,562 @@
+use crate::class::classrooms::{is_classroom_available, is_classroom_compatible};
+use crate::class::school_calendar::is_calendar_slot;
+use crate::class::teachers::{is_teacher_unavailable, Teacher};
+use crate::db::{AppState, DbPool};
+use crate::util::assignments::planned_teacher;
+use crate::util::calendar::{parse_week, weeks_overlap, MODULES_PER_DAY, SCHOOL_DAYS};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Regla que se romperia al poner una materia en un modulo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotRule {
+    /// El dia o el modulo no estan en el calendario escolar
+    OutsideCalendar,
+    /// El subgrupo no es del grupo
+    SubgroupMismatch,
+    /// El modulo ya es de todo el grupo o ya esta dividido entre subgrupos
+    GroupBusy,
+    /// La materia no esta ligada al grupo
+    SubjectNotInGroup,
+    /// La materia ya tiene todas sus horas en el grupo
+    SubjectHoursMet,
+    /// No se mando profesor y la materia no tiene profesor en el plan de carga
+    NoTeacher,
+    /// El profesor ya tiene clase con otro grupo en ese modulo
+    TeacherBusy,
+    /// El profesor no puede trabajar en ese modulo
+    TeacherUnavailable,
+    /// El salon ya esta ocupado por otro grupo en ese modulo
+    ClassroomBusy,
+    /// El salon no se puede usar en ese modulo
+    ClassroomUnavailable,
+    /// El salon no es del tipo que requiere la materia
+    ClassroomIncompatible,
+}
+
+/// Modulo que se quiere revisar
+/// Si no se manda `teacher_id` se usa el profesor del plan de carga del grupo
+/// Si no se manda `week` la clase es todas las semanas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotCheck {
+    pub group_id: i16,
+    pub day: String,
+    pub module_index: i16,
+    pub subject_id: i16,
+    pub teacher_id: Option<i16>,
+    pub classroom_id: Option<i16>,
+    pub subgroup_id: Option<i16>,
+    #[serde(default)]
+    pub week: Option<String>,
+}
+
+/// Regla rota junto con las asignaciones que la provocan
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlotViolation {
+    pub rule: SlotRule,
+    pub message: String,
+    pub assignment_ids: Vec<i16>,
+}
+
+/// Modulo donde se puede poner una materia
+/// `penalty` indica cuanto empeora el horario (menor es mejor) y `reasons` el por que
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlotSuggestion {
+    pub day: String,
+    pub module_index: i16,
+    pub teacher_id: i16,
+    pub penalty: i16,
+    pub reasons: Vec<String>,
+}
+
+impl SlotViolation {
+    fn new(rule: SlotRule, message: String, assignment_ids: Vec<i16>) -> Self {
+        SlotViolation {
+            rule,
+            message,
+            assignment_ids,
+        }
+    }
+}
+
+/// Funcion para explicar por que una materia no se puede poner en un modulo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `slot` - Grupo, dia, modulo, materia, profesor, salon y subgrupo a revisar
+/// Retorna todas las reglas que se romperian, vacio si la materia se puede poner
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn explain_slot(
+    pool: tauri::State<'_, AppState>,
+    slot: SlotCheck,
+) -> Result<Vec<SlotViolation>, String> {
+    slot_violations(&pool.db, &slot).await
+}
+
+/// Funcion para sugerir los modulos donde se puede poner una materia de un grupo
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `group_id` - ID del grupo
+/// * `subject_id` - ID de la materia
+/// * `teacher_id` - ID del profesor (opcional, si es nulo se usa el del plan de carga
+///   o el primero ligado a la materia)
+/// * `subgroup_id` - ID del subgrupo (opcional)
+/// Solo regresa modulos libres del grupo que no rompen ninguna regla, ordenados de
+/// menor a mayor penalizacion (huecos, preferencias del profesor, materia repetida el mismo dia)
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn suggest_slots(
+    pool: tauri::State<'_, AppState>,
+    group_id: i16,
+    subject_id: i16,
+    teacher_id: Option<i16>,
+    subgroup_id: Option<i16>,
+) -> Result<Vec<SlotSuggestion>, String> {
+    let teacher_id = match teacher_id {
+        Some(teacher_id) => teacher_id,
+        None => match planned_teacher(&pool.db, group_id as i32, subject_id as i32).await? {
+            Some(teacher_id) => teacher_id as i16,
+            None => sqlx::query_scalar(
+                "SELECT teacher_id FROM teacher_subjects WHERE subject_id = ?1 ORDER BY teacher_id",
+            )
+            .bind(subject_id)
+            .fetch_optional(&pool.db)
+            .await
+            .map_err(|e| format!("Error getting the subject teacher: {}", e))?
+            .ok_or_else(|| format!("Subject {} has no linked teacher", subject_id))?,
+        },
+    };
+
+    let teacher: Teacher = sqlx::query_as::<_, Teacher>("SELECT * FROM teachers WHERE id = ?1")
+        .bind(teacher_id)
+        .fetch_optional(&pool.db)
+        .await
+        .map_err(|e| format!("Error getting the teacher: {}", e))?
+        .ok_or_else(|| format!("Teacher {} not found", teacher_id))?;
+
+    // Modulos ocupados del grupo (o subgrupo) y del profesor: (dia, modulo, materia)
+    let group_slots: Vec<(String, i16, i16)> = sqlx::query_as(
+        "
+        SELECT DISTINCT day, module_index, subject_id FROM assignments
+        WHERE group_id = ?1 AND (subgroup_id IS NULL OR ?2 IS NULL OR subgroup_id = ?2)
+        ",
+    )
+    .bind(group_id)
+    .bind(subgroup_id)
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Error getting the group modules: {}", e))?;
+
+    let teacher_slots: Vec<(String, i16)> =
+        sqlx::query_as("SELECT DISTINCT day, module_index FROM assignments WHERE teacher_id = ?1")
+            .bind(teacher_id)
+            .fetch_all(&pool.db)
+            .await
+            .map_err(|e| format!("Error getting the teacher modules: {}", e))?;
+
+    let mut suggestions: Vec<SlotSuggestion> = Vec::new();
+    for day in SCHOOL_DAYS {
+        // Los subgrupos pueden tener clases en paralelo, cada modulo cuenta una vez
+        let group_day: Vec<i16> = group_slots
+            .iter()
+            .filter(|(d, _, _)| d == day)
+            .map(|(_, m, _)| *m)
+            .collect::<BTreeSet<i16>>()
+            .into_iter()
+            .collect();
+        let teacher_day: Vec<i16> = teacher_slots
+            .iter()
+            .filter(|(d, _)| d == day)
+            .map(|(_, m)| *m)
+            .collect();
+        let repeated = group_slots
+            .iter()
+            .filter(|(d, _, s)| d == day && *s == subject_id)
+            .map(|(_, m, _)| *m)
+            .collect::<BTreeSet<i16>>()
+            .len() as i16;
+
+        for module_index in 0..MODULES_PER_DAY {
+            // Solo modulos libres, no se sugiere reemplazar otra clase
+            if group_day.contains(&module_index) {
+                continue;
+            }
+
+            let slot = SlotCheck {
+                group_id,
+                day: day.to_string(),
+                module_index,
+                subject_id,
+                teacher_id: Some(teacher_id),
+                classroom_id: None,
+                subgroup_id,
+                week: None,
+            };
+            if !slot_violations(&pool.db, &slot).await?.is_empty() {
+                continue;
+            }
+
+            let mut penalty = 0;
+            let mut reasons: Vec<String> = Vec::new();
+
+            if repeated > 0 {
+                penalty += 3 * repeated;
+                reasons.push(format!(
+                    "Subject already has {} module(s) on {}",
+                    repeated, day
+                ));
+            }
+
+            let group_gaps = gaps_added(&group_day, module_index);
+            if group_gaps != 0 {
+                penalty += 2 * group_gaps;
+                reasons.push(format!("Changes the group gaps by {}", group_gaps));
+            }
+
+            let teacher_gaps = gaps_added(&teacher_day, module_index);
+            if teacher_gaps != 0 {
+                penalty += teacher_gaps;
+                reasons.push(format!("Changes the teacher gaps by {}", teacher_gaps));
+            }
+
+            if !teacher.preferred_days.is_empty()
+                && !teacher.preferred_days.iter().any(|d| d == day)
+            {
+                penalty += 2;
+                reasons.push(format!("{} is not a preferred day of the teacher", day));
+            }
+
+            if !teacher.preferred_modules.is_empty()
+                && !teacher.preferred_modules.contains(&module_index)
+            {
+                penalty += 1;
+                reasons.push(format!(
+                    "Module {} is not a preferred module of the teacher",
+                    module_index
+                ));
+            }
+
+            suggestions.push(SlotSuggestion {
+                day: day.to_string(),
+                module_index,
+                teacher_id,
+                penalty,
+                reasons,
+            });
+        }
+    }
+
+    // El orden es estable, a igual penalizacion quedan por dia y modulo
+    suggestions.sort_by_key(|s| s.penalty);
+
+    Ok(suggestions)
+}
+
+/// Cuenta los modulos libres entre el primer y el ultimo modulo ocupado de un dia
+fn gaps(modules: &[i16]) -> i16 {
+    let modules: BTreeSet<&i16> = modules.iter().collect();
+    match (modules.first(), modules.last()) {
+        (Some(first), Some(last)) => *last - *first + 1 - modules.len() as i16,
+        _ => 0,
+    }
+}
+
+/// Cuantos huecos se agregan (o se quitan, si es negativo) al ocupar el modulo
+fn gaps_added(modules: &[i16], module_index: i16) -> i16 {
+    let mut after: Vec<i16> = modules.to_vec();
+    if !after.contains(&module_index) {
+        after.push(module_index);
+    }
+    gaps(&after) - gaps(modules)
+}
+
+/// Revisa todas las reglas del horario para un modulo
+/// La asignacion que ya ocupa el mismo lugar (grupo o subgrupo) no cuenta como conflicto
+/// porque al guardar se reemplaza
+pub async fn slot_violations(
+    pool: &DbPool,
+    slot: &SlotCheck,
+) -> Result<Vec<SlotViolation>, String> {
+    let mut violations: Vec<SlotViolation> = Vec::new();
+    let day = slot.day.as_str();
+    let week = parse_week(slot.week.as_deref())?;
+
+    if !is_calendar_slot(pool, day, slot.module_index).await? {
+        violations.push(SlotViolation::new(
+            SlotRule::OutsideCalendar,
+            format!(
+                "Module {} on {} is outside the school calendar",
+                slot.module_index, day
+            ),
+            Vec::new(),
+        ));
+    }
+
+    if let Some(subgroup_id) = slot.subgroup_id {
+        let belongs: Option<i16> =
+            sqlx::query_scalar("SELECT 1 FROM subgroups WHERE id = ?1 AND group_id = ?2")
+                .bind(subgroup_id)
+                .bind(slot.group_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| format!("Error checking the subgroup: {}", e))?;
+        if belongs.is_none() {
+            violations.push(SlotViolation::new(
+                SlotRule::SubgroupMismatch,
+                format!(
+                    "Subgroup {} does not belong to group {}",
+                    subgroup_id, slot.group_id
+                ),
+                Vec::new(),
+            ));
+        }
+    }
+
+    // Asignaciones del grupo en el modulo: (id, subgrupo, semana)
+    let group_rows: Vec<(i16, Option<i16>, String)> = sqlx::query_as(
+        "
+        SELECT id, subgroup_id, week FROM assignments
+        WHERE group_id = ?1 AND day = ?2 AND module_index = ?3
+        ",
+    )
+    .bind(slot.group_id)
+    .bind(day)
+    .bind(slot.module_index)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Error checking the module: {}", e))?;
+
+    // Los subgrupos pueden compartir el modulo entre ellos, pero no con todo el grupo,
+    // y solo se reemplaza la asignacion del mismo lugar con el mismo patron de semanas
+    let blocking: Vec<&(i16, Option<i16>, String)> = group_rows
+        .iter()
+        .filter(|(_, subgroup_id, w)| {
+            weeks_overlap(w, week)
+                && !(*subgroup_id == slot.subgroup_id && w == week)
+                && (slot.subgroup_id.is_none()
+                    || subgroup_id.is_none()
+                    || *subgroup_id == slot.subgroup_id)
+        })
+        .collect();
+    if let Some((_, subgroup_id, w)) = blocking.first() {
+        let message = match (slot.subgroup_id, subgroup_id) {
+            (Some(_), None) => format!(
+                "Module {} on {} is already assigned to the whole group",
+                slot.module_index, day
+            ),
+            (None, Some(_)) => format!(
+                "Module {} on {} is split between subgroups",
+                slot.module_index, day
+            ),
+            _ => format!(
+                "Module {} on {} already has a class in week '{}'",
+                slot.module_index, day, w
+            ),
+        };
+        let ids = blocking.iter().map(|(id, _, _)| *id).collect();
+        violations.push(SlotViolation::new(SlotRule::GroupBusy, message, ids));
+    }
+
+    let hours: Option<i16> = sqlx::query_scalar(
+        "
+        SELECT COALESCE(gs.hours, s.required_modules)
+        FROM groups_subjects gs
+        JOIN subjects s ON s.id = gs.subject_id
+        WHERE gs.group_id = ?1 AND gs.subject_id = ?2
+        ",
+    )
+    .bind(slot.group_id)
+    .bind(slot.subject_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Error getting the subject hours: {}", e))?;
+
+    match hours {
+        None => violations.push(SlotViolation::new(
+            SlotRule::SubjectNotInGroup,
+            format!(
+                "Subject {} is not linked to group {}",
+                slot.subject_id, slot.group_id
+            ),
+            Vec::new(),
+        )),
+        Some(hours) => {
+            // Modulos que ya tiene la materia sin contar el que se reemplazaria
+            let assigned: Vec<(i16, String)> = sqlx::query_as(
+                "
+                SELECT id, week FROM assignments
+                WHERE group_id = ?1 AND subject_id = ?2 AND subgroup_id IS ?3
+                AND NOT (day = ?4 AND module_index = ?5 AND week = ?6)
+                ORDER BY id
+                ",
+            )
+            .bind(slot.group_id)
+            .bind(slot.subject_id)
+            .bind(slot.subgroup_id)
+            .bind(day)
+            .bind(slot.module_index)
+            .bind(week)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Error counting the subject hours: {}", e))?;
+
+            // Se cuenta en medios modulos: una clase quincenal es medio modulo por semana
+            let half_modules = |w: &str| if w == "all" { 2 } else { 1 };
+            let used: i16 = assigned.iter().map(|(_, w)| half_modules(w)).sum();
+            if used + half_modules(week) > 2 * hours {
+                let assigned = assigned.into_iter().map(|(id, _)| id).collect();
+                violations.push(SlotViolation::new(
+                    SlotRule::SubjectHoursMet,
+                    format!(
+                        "Subject {} already has its {} modules in group {}",
+                        slot.subject_id, hours, slot.group_id
+                    ),
+                    assigned,
+                ));
+            }
+        }
+    }
+
+    let teacher_id = match slot.teacher_id {
+        Some(teacher_id) => Some(teacher_id),
+        None => planned_teacher(pool, slot.group_id as i32, slot.subject_id as i32)
+            .await?
+            .map(|t| t as i16),
+    };
+
+    match teacher_id {
+        None => violations.push(SlotViolation::new(
+            SlotRule::NoTeacher,
+            format!(
+                "No teacher given and subject {} has no planned teacher for group {}",
+                slot.subject_id, slot.group_id
+            ),
+            Vec::new(),
+        )),
+        Some(teacher_id) => {
+            let busy = busy_assignments(pool, slot, "teacher_id", teacher_id).await?;
+            if !busy.is_empty() {
+                violations.push(SlotViolation::new(
+                    SlotRule::TeacherBusy,
+                    format!(
+                        "Teacher {} already has a class on {} at module {}",
+                        teacher_id, day, slot.module_index
+                    ),
+                    busy,
+                ));
+            }
+
+            if is_teacher_unavailable(pool, teacher_id, day, slot.module_index).await? {
+                violations.push(SlotViolation::new(
+                    SlotRule::TeacherUnavailable,
+                    format!(
+                        "Teacher {} is not available on {} at module {}",
+                        teacher_id, day, slot.module_index
+                    ),
+                    Vec::new(),
+                ));
+            }
+        }
+    }
+
+    if let Some(classroom_id) = slot.classroom_id {
+        let busy = busy_assignments(pool, slot, "classroom_id", classroom_id).await?;
+        if !busy.is_empty() {
+            violations.push(SlotViolation::new(
+                SlotRule::ClassroomBusy,
+                format!(
+                    "Classroom {} is already used on {} at module {}",
+                    classroom_id, day, slot.module_index
+                ),
+                busy,
+            ));
+        }
+
+        if !is_classroom_available(pool, classroom_id, day, slot.module_index).await? {
+            violations.push(SlotViolation::new(
+                SlotRule::ClassroomUnavailable,
+                format!(
+                    "Classroom {} is not available on {} at module {}",
+                    classroom_id, day, slot.module_index
+                ),
+                Vec::new(),
+            ));
+        }
+
+        if !is_classroom_compatible(pool, classroom_id, slot.subject_id).await? {
+            violations.push(SlotViolation::new(
+                SlotRule::ClassroomIncompatible,
+                format!(
+                    "Classroom {} does not meet the room requirements of subject {}",
+                    classroom_id, slot.subject_id
+                ),
+                Vec::new(),
+            ));
+        }
+    }
+
+    Ok(violations)
+}
+
+/// Asignaciones de otros grupos que ocupan al profesor o salon en el modulo
+/// `column` es `teacher_id` o `classroom_id`
+async fn busy_assignments(
+    pool: &DbPool,
+    slot: &SlotCheck,
+    column: &str,
+    id: i16,
+) -> Result<Vec<i16>, String> {
+    let week = parse_week(slot.week.as_deref())?;
+    sqlx::query_scalar(&format!(
+        "
+        SELECT id FROM assignments
+        WHERE {} = ?1 AND day = ?2 AND module_index = ?3
+        AND NOT (group_id = ?4 AND subgroup_id IS ?5 AND week = ?6)
+        AND (week = 'all' OR ?6 = 'all' OR week = ?6)
+        ORDER BY id
+        ",
+        column
+    ))
+    .bind(id)
+    .bind(&slot.day)
+    .bind(slot.module_index)
+    .bind(slot.group_id)
+    .bind(slot.subgroup_id)
+    .bind(week)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Error checking the {}: {}", column, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gaps_counts_free_modules_between_classes() {
+        assert_eq!(gaps(&[]), 0);
+        assert_eq!(gaps(&[3]), 0);
+        assert_eq!(gaps(&[1, 2, 3]), 0);
+        assert_eq!(gaps(&[1, 4]), 2);
+        assert_eq!(gaps(&[0, 2, 5]), 3);
+    }
+
+    #[test]
+    fn gaps_ignores_parallel_classes_in_one_module() {
+        // Dos subgrupos en el modulo 2 no son un hueco negativo
+        assert_eq!(gaps(&[1, 2, 2, 3]), 0);
+        assert_eq!(gaps(&[1, 1, 3]), 1);
+    }
+
+    #[test]
+    fn gaps_added_by_a_new_module() {
+        assert_eq!(gaps_added(&[], 4), 0);
+        assert_eq!(gaps_added(&[1, 2], 3), 0);
+        assert_eq!(gaps_added(&[1, 2], 5), 2);
+        // Llenar un hueco lo quita
+        assert_eq!(gaps_added(&[1, 3], 2), -1);
+        // Un modulo ya ocupado no cambia nada
+        assert_eq!(gaps_added(&[1, 3], 3), 0);
+    }
+}
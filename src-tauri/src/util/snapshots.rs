@@ -0,0 +1,394 @@
+use crate::db::{AppState, DbPool};
+use crate::util::assignments::delete_empty_combined;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::{Executor, Sqlite};
+use std::collections::BTreeMap;
+
+/// Estructura de una copia con nombre del horario
+/// `modules` es la cantidad de asignaciones guardadas
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Snapshot {
+    pub id: i16,
+    pub name: String,
+    pub created_at: String,
+    pub modules: i16,
+}
+
+/// Tipo de cambio de un modulo entre dos horarios
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Moved,
+}
+
+/// Dia, modulo y patron de semanas del horario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleSlot {
+    pub day: String,
+    pub module_index: i16,
+    pub week: String,
+}
+
+/// Modulo que cambio entre dos horarios
+/// `from` es nulo en los agregados y `to` es nulo en los quitados
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModuleChange {
+    pub kind: ChangeKind,
+    pub group_id: i16,
+    pub subgroup_id: Option<i16>,
+    pub subject_id: i16,
+    pub teacher_id: i16,
+    pub from: Option<ScheduleSlot>,
+    pub to: Option<ScheduleSlot>,
+}
+
+/// Cantidad de cambios de un grupo o de un profesor
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ChangeSummary {
+    pub id: i16,
+    pub added: i16,
+    pub removed: i16,
+    pub moved: i16,
+}
+
+/// Diferencias entre dos horarios
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub changes: Vec<ModuleChange>,
+    pub groups: Vec<ChangeSummary>,
+    pub teachers: Vec<ChangeSummary>,
+}
+
+/// Fila de un horario para compararla
+#[derive(Debug, FromRow)]
+struct SnapshotRow {
+    group_id: i16,
+    subgroup_id: Option<i16>,
+    subject_id: i16,
+    teacher_id: i16,
+    day: String,
+    module_index: i16,
+    week: String,
+}
+
+/// Funcion para guardar el horario actual como una copia con nombre
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `name` - Nombre de la copia (ej: Borrador antes de revision)
+/// Retorna el ID de la copia si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn create_snapshot(
+    pool: tauri::State<'_, AppState>,
+    name: String,
+) -> Result<i16, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("The snapshot name is empty".to_string());
+    }
+
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    let id: i16 =
+        sqlx::query_scalar("INSERT INTO schedule_snapshots (name) VALUES (?1) RETURNING id")
+            .bind(name)
+            .fetch_one(&mut tx)
+            .await
+            .map_err(|e| format!("Failed to create snapshot: {}", e))?;
+
+    sqlx::query(
+        "
+        INSERT INTO snapshot_assignments
+            (snapshot_id, group_id, day, module_index, subject_id, teacher_id, classroom_id,
+             subgroup_id, combined_id, week)
+        SELECT ?1, group_id, day, module_index, subject_id, teacher_id, classroom_id,
+               subgroup_id, combined_id, week
+        FROM assignments
+        ",
+    )
+    .bind(id)
+    .execute(&mut tx)
+    .await
+    .map_err(|e| format!("Failed to save snapshot assignments: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(id)
+}
+
+/// Funcion para obtener las copias guardadas del horario
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// Retorna un vector con las copias, de la mas reciente a la mas antigua
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn get_snapshots(pool: tauri::State<'_, AppState>) -> Result<Vec<Snapshot>, String> {
+    let snapshots: Vec<Snapshot> = sqlx::query_as::<_, Snapshot>(
+        "
+        SELECT s.id, s.name, s.created_at,
+               (SELECT COUNT(*) FROM snapshot_assignments a WHERE a.snapshot_id = s.id) AS modules
+        FROM schedule_snapshots s
+        ORDER BY s.created_at DESC, s.id DESC
+        ",
+    )
+    .fetch_all(&pool.db)
+    .await
+    .map_err(|e| format!("Failed to get snapshots: {}", e))?;
+
+    Ok(snapshots)
+}
+
+/// Funcion para eliminar una copia del horario
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID de la copia
+/// Retorna un resultado vacio si la operacion fue exitosa
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn delete_snapshot(pool: tauri::State<'_, AppState>, id: i16) -> Result<(), String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    sqlx::query("DELETE FROM snapshot_assignments WHERE snapshot_id = ?1")
+        .bind(id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to delete snapshot assignments: {}", e))?;
+
+    sqlx::query("DELETE FROM schedule_snapshots WHERE id = ?1")
+        .bind(id)
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to delete snapshot: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(())
+}
+
+/// Funcion para regresar el horario a una copia guardada
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `id` - ID de la copia
+/// Reemplaza todas las asignaciones actuales. Las asignaciones de grupos, materias,
+/// profesores, subgrupos o clases compartidas que ya no existen se omiten y los salones
+/// borrados se dejan vacios
+/// Retorna la cantidad de asignaciones restauradas
+#[allow(dead_code, unused)]
+#[tauri::command]
+pub async fn restore_snapshot(pool: tauri::State<'_, AppState>, id: i16) -> Result<u64, String> {
+    let mut tx = pool
+        .db
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction! {}", e))?;
+
+    check_snapshot(&mut tx, id).await?;
+
+    sqlx::query("DELETE FROM assignments")
+        .execute(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to clear assignments: {}", e))?;
+
+    let restored = sqlx::query(
+        "
+        INSERT INTO assignments
+            (group_id, day, module_index, subject_id, teacher_id, classroom_id, subgroup_id,
+             combined_id, week)
+        SELECT a.group_id, a.day, a.module_index, a.subject_id, a.teacher_id,
+               (SELECT id FROM classroom WHERE id = a.classroom_id),
+               a.subgroup_id, a.combined_id, a.week
+        FROM snapshot_assignments a
+        JOIN groups g ON g.id = a.group_id
+        JOIN subjects s ON s.id = a.subject_id
+        JOIN teachers t ON t.id = a.teacher_id
+        WHERE a.snapshot_id = ?1
+        AND (a.subgroup_id IS NULL OR EXISTS (
+            SELECT 1 FROM subgroups sg WHERE sg.id = a.subgroup_id AND sg.group_id = a.group_id
+        ))
+        AND (a.combined_id IS NULL OR EXISTS (
+            SELECT 1 FROM combined_classes c WHERE c.id = a.combined_id
+        ))
+        ",
+    )
+    .bind(id)
+    .execute(&mut tx)
+    .await
+    .map_err(|e| format!("Failed to restore snapshot: {}", e))?;
+
+    // Las clases compartidas que solo estaban en el horario reemplazado quedan vacias
+    delete_empty_combined(&mut tx).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction! {}", e))?;
+
+    Ok(restored.rows_affected())
+}
+
+/// Funcion para comparar dos horarios
+/// # Argumentos
+/// * `pool` - Conexion a la base de datos
+/// * `from_id` - ID de la copia original (opcional, si es nulo se usa el horario actual)
+/// * `to_id` - ID de la copia nueva (opcional, si es nulo se usa el horario actual)
+/// Un modulo se considera movido si la misma materia con el mismo profesor cambia de dia
+/// o modulo dentro del grupo
+/// Retorna los cambios y un resumen por grupo y por profesor
+#[allow(dead_code, unused)]
+#[tauri::command(rename_all = "snake_case")]
+pub async fn diff_snapshots(
+    pool: tauri::State<'_, AppState>,
+    from_id: Option<i16>,
+    to_id: Option<i16>,
+) -> Result<SnapshotDiff, String> {
+    let before = fetch_rows(&pool.db, from_id).await?;
+    let after = fetch_rows(&pool.db, to_id).await?;
+
+    // Se agrupan por clase (grupo, subgrupo, materia y profesor) y se quitan los modulos
+    // que no cambiaron
+    type ClassKey = (i16, Option<i16>, i16, i16);
+    let mut classes: BTreeMap<ClassKey, (Vec<ScheduleSlot>, Vec<ScheduleSlot>)> = BTreeMap::new();
+    for (rows, is_after) in [(before, false), (after, true)] {
+        for row in rows {
+            let key = (
+                row.group_id,
+                row.subgroup_id,
+                row.subject_id,
+                row.teacher_id,
+            );
+            let slot = ScheduleSlot {
+                day: row.day,
+                module_index: row.module_index,
+                week: row.week,
+            };
+            let (removed, added) = classes.entry(key).or_default();
+            if is_after {
+                added.push(slot);
+            } else {
+                removed.push(slot);
+            }
+        }
+    }
+
+    let mut changes: Vec<ModuleChange> = Vec::new();
+    for ((group_id, subgroup_id, subject_id, teacher_id), (mut removed, mut added)) in classes {
+        removed.retain(|slot| match added.iter().position(|a| a == slot) {
+            Some(i) => {
+                added.remove(i);
+                false
+            }
+            None => true,
+        });
+
+        let moved = removed.len().min(added.len());
+        let change = |kind, from, to| ModuleChange {
+            kind,
+            group_id,
+            subgroup_id,
+            subject_id,
+            teacher_id,
+            from,
+            to,
+        };
+        for (i, from) in removed.into_iter().enumerate() {
+            if i < moved {
+                changes.push(change(
+                    ChangeKind::Moved,
+                    Some(from),
+                    Some(added[i].clone()),
+                ));
+            } else {
+                changes.push(change(ChangeKind::Removed, Some(from), None));
+            }
+        }
+        for to in added.into_iter().skip(moved) {
+            changes.push(change(ChangeKind::Added, None, Some(to)));
+        }
+    }
+
+    let mut groups: BTreeMap<i16, ChangeSummary> = BTreeMap::new();
+    let mut teachers: BTreeMap<i16, ChangeSummary> = BTreeMap::new();
+    for change in &changes {
+        for (summaries, id) in [
+            (&mut groups, change.group_id),
+            (&mut teachers, change.teacher_id),
+        ] {
+            let summary = summaries.entry(id).or_insert_with(|| ChangeSummary {
+                id,
+                ..Default::default()
+            });
+            match change.kind {
+                ChangeKind::Added => summary.added += 1,
+                ChangeKind::Removed => summary.removed += 1,
+                ChangeKind::Moved => summary.moved += 1,
+            }
+        }
+    }
+
+    Ok(SnapshotDiff {
+        changes,
+        groups: groups.into_values().collect(),
+        teachers: teachers.into_values().collect(),
+    })
+}
+
+/// Revisa que exista la copia
+async fn check_snapshot<'c, E>(executor: E, id: i16) -> Result<(), String>
+where
+    E: Executor<'c, Database = Sqlite>,
+{
+    let exists: Option<i16> = sqlx::query_scalar("SELECT 1 FROM schedule_snapshots WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| format!("Failed to get snapshot: {}", e))?;
+
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(format!("Snapshot {} not found", id)),
+    }
+}
+
+/// Obtiene las filas de una copia o del horario actual si no se manda ID
+async fn fetch_rows(pool: &DbPool, snapshot_id: Option<i16>) -> Result<Vec<SnapshotRow>, String> {
+    if let Some(id) = snapshot_id {
+        check_snapshot(pool, id).await?;
+    }
+
+    let query = match snapshot_id {
+        Some(_) => {
+            "
+            SELECT group_id, subgroup_id, subject_id, teacher_id, day, module_index, week
+            FROM snapshot_assignments WHERE snapshot_id = ?1
+            ORDER BY group_id, day, module_index
+            "
+        }
+        None => {
+            "
+            SELECT group_id, subgroup_id, subject_id, teacher_id, day, module_index, week
+            FROM assignments
+            ORDER BY group_id, day, module_index
+            "
+        }
+    };
+
+    sqlx::query_as::<_, SnapshotRow>(query)
+        .bind(snapshot_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to get schedule: {}", e))
+}
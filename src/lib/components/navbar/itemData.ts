@@ -39,6 +39,7 @@ export const itemData = [
   { it: 0, name: "Materias", icon: "/icons/books.svg", menu: "subjects", submenu: [] },
   { it: 0, name: "Profesores", icon: "/icons/teacher.svg", menu: "teachers", submenu: [] },
   { it: 0, name: "Aulas", icon: "/icons/door.svg", menu: "classroom", submenu: [] },
+  { it: 0, name: "Alumnos", icon: "/icons/group.svg", menu: "students", submenu: [] },
 
   { it: 3, name: "IA", icon: "/icons/robot.svg", menu: "todo", submenu: [] },
   { it: 0, name: "Historial", icon: "/icons/clock.svg", menu: "todo", submenu: [] },
@@ -7,6 +7,9 @@ import { writable, type Writable } from "svelte/store";
   * @property {string} day - Dia asignado
   * @property {number} module_index - Espacio (modulo)
   * @property {number} subject_id - Id de la materia
+  * @property {number} classroom_id - Id del aula (si aplica)
+  * @property {number} subgroup_id - Id del subgrupo (si la clase es solo para una parte del grupo)
+  * @property {string} week - Patron de semanas: "all" (todas), "a" o "b" (quincenal)
   */
 export interface AssignmentItem {
   id: number;
@@ -15,6 +18,9 @@ export interface AssignmentItem {
   module_index: number,
   subject_id: number,
   teacher_id: number,
+  classroom_id?: number,
+  subgroup_id?: number,
+  week?: string,
   subject_shorten: string,
   subject_color: string
 }
@@ -26,29 +32,40 @@ export async function loadAssignments(): Promise<void> {
   const response = await invoke("get_all_assignments");
   const assignments = response as AssignmentItem[];
 
+  // Cada modulo guarda una lista: los subgrupos y las semanas A/B pueden tener clases en paralelo
   const newAssignmentsMap = new Map();
   assignments.forEach((assignment) => {
     const key = `${assignment.group_id}-${assignment.day}-${assignment.module_index}`;
-    newAssignmentsMap.set(key, {
+    const shorten = assignment.week && assignment.week !== "all"
+      ? `${assignment.subject_shorten} (${assignment.week.toUpperCase()})`
+      : assignment.subject_shorten;
+    const classes = newAssignmentsMap.get(key) ?? [];
+    classes.push({
       id: assignment.id,
-      shorten: assignment.subject_shorten,
+      shorten,
       color: assignment.subject_color,
       teacherId: assignment.teacher_id,
       subjectId: assignment.subject_id,
+      subgroupId: assignment.subgroup_id,
     });
+    newAssignmentsMap.set(key, classes);
+  });
+  newAssignmentsMap.forEach((classes) => {
+    classes.sort((a: any, b: any) => (a.subgroupId ?? 0) - (b.subgroupId ?? 0));
   });
 
   assignmentsStore.set(newAssignmentsMap);
 }
 
 // Manera eficiente de conseguir las asignaciones sin llamar a la base de datos
-export function getLocalAssignment(groupId: number, day: string, moduleIndex: number) {
+// Retorna todas las clases del modulo (mas de una si el grupo esta dividido)
+export function getLocalAssignments(groupId: number, day: string, moduleIndex: number): any[] {
   const key = `${groupId}-${day}-${moduleIndex}`;
-  let assignment;
+  let assignments: any[] = [];
   assignmentsStore.subscribe((map: any) => {
-    assignment = map.get(key);
+    assignments = map.get(key) ?? [];
   })();
-  return assignment;
+  return assignments;
 }
 
 // Funcion para cuando se suelta una materia en el modulo
@@ -81,7 +98,8 @@ export async function handleAssignDrop(
     });
     assignmentsStore.update((currentMap) => {
       const newMap = new Map(currentMap);
-      newMap.set(key, subject);
+      // Una clase de todo el grupo ocupa el modulo completo
+      newMap.set(key, [subject]);
       return newMap;
     });
   } catch (error) {
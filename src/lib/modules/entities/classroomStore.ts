@@ -9,13 +9,25 @@ import { emit } from "@tauri-apps/api/event";
   * @property {number} building_number - Numero del aula
   * @property {string} building_type - Tipo de aula (ej: Laboratorio) (si aplica)
   * @property {number} capacity - Capacidad del aula (si aplica)
+  * @property {AvailabilityBlock[]} availability - Bloques en los que no se puede usar el aula
   */
 export interface ClassroomItem {
   id: number;
   building_id: string,
   building_number: number;
   building_type: string,
-  capacity: number
+  capacity: number,
+  availability?: AvailabilityBlock[]
+}
+
+/**
+  * Bloque (dia y modulo) en el que el aula no esta disponible
+  * @property {string} day - Dia
+  * @property {number} module_index - Modulo
+  */
+export interface AvailabilityBlock {
+  day: string,
+  module_index: number
 }
 
 /**
@@ -0,0 +1,94 @@
+import { writable } from "svelte/store";
+import { invoke } from "@tauri-apps/api";
+import { emit } from "@tauri-apps/api/event";
+
+/**
+  * Interfaz para los datos de los alumnos
+  * @property {number} id - Identificador único
+  * @property {string} name - Nombre del alumno
+  * @property {string} father_lastname - Apellido paterno
+  * @property {string} mother_lastname - Apellido materno (opcional)
+  * @property {string} enrollment - Matricula (opcional)
+  * @property {string} email - Correo electrónico (opcional)
+  * @property {number} group_id - Grupo en el que esta inscrito (opcional)
+  */
+export interface StudentItem {
+  id?: number;
+  name: string;
+  father_lastname: string;
+  mother_lastname?: string;
+  enrollment?: string;
+  email?: string;
+  group_id?: number;
+}
+
+/**
+ * Lista todos los alumnos registrados
+ */
+export const students = writable<StudentItem[]>([]);
+
+/**
+ * Carga los alumnos desde la base de datos
+ */
+export async function loadStudents(): Promise<void> {
+  const response = await invoke("get_students");
+  students.set(response as StudentItem[]);
+}
+
+/**
+  * Funcion para importar varios alumnos, se utiliza en ImportExcel
+  * El grupo se busca por grado y grupo (ej: 1 y A)
+  * @param {Record<string, string>} headerMappings
+  * @param {Array<Record<string, unknown>>} excelData
+  */
+export async function importStudentsFromXlsx(
+  headerMappings: Record<string, string>,
+  excelData: Array<Record<string, unknown>>
+): Promise<void> {
+  // Checar por campos requeridos no importados
+  const required: string[] = ['name', 'father_lastname'];
+  const missingFields: string[] = required.filter(
+    field => !headerMappings[field]
+  );
+  if (missingFields.length > 0) {
+    throw new Error(`Faltan campos necesarios: ${missingFields.join(',')}`);
+  }
+
+  // Preparar los alumnos que seran importados
+  const studentsToImport = excelData
+    .map(row => {
+      return {
+        name: String(row[headerMappings.name] || ''),
+        father_lastname: String(row[headerMappings.father_lastname] || ''),
+        mother_lastname: headerMappings.mother_lastname
+          ? String(row[headerMappings.mother_lastname] || '')
+          : null,
+        enrollment: headerMappings.enrollment
+          ? String(row[headerMappings.enrollment] || '')
+          : null,
+        email: headerMappings.email
+          ? String(row[headerMappings.email] || '')
+          : null,
+        grade: headerMappings.grade
+          ? Number(row[headerMappings.grade]) || null
+          : null,
+        group: headerMappings.group
+          ? String(row[headerMappings.group] || '')
+          : null,
+      };
+    })
+    .filter(student => student.name && student.father_lastname);
+
+  if (studentsToImport.length === 0) {
+    throw new Error('No hay alumnos validos en el intento de importar datos');
+  }
+
+  try {
+    await invoke("create_students", { students: studentsToImport });
+    await loadStudents();
+    await emit("students_updated");
+  } catch (error) {
+    console.error('Hubo un error importando los alumnos:', error);
+    throw error;
+  }
+}